@@ -0,0 +1,523 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+/// An integer of arbitrary size, stored as its sign and the base 2^32
+/// digits of its magnitude, least significant first. The magnitude never
+/// has leading zero digits, and zero is never negative, so equal integers
+/// have equal representations.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        BigInt {
+            negative,
+            magnitude,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Parses unsigned digits in the given radix, e.g. "ff" in radix 16.
+    pub fn from_str_radix(digits: &str, radix: u32) -> Option<Self> {
+        if digits.is_empty() {
+            return None;
+        }
+        let mut magnitude = Vec::new();
+        for c in digits.chars() {
+            let digit = c.to_digit(radix)?;
+            let mut carry = digit as u64;
+            for limb in magnitude.iter_mut() {
+                let value = *limb as u64 * radix as u64 + carry;
+                *limb = value as u32;
+                carry = value >> 32;
+            }
+            if carry > 0 {
+                magnitude.push(carry as u32);
+            }
+        }
+        Some(BigInt::new(false, magnitude))
+    }
+
+    /// Converts the integer part of a finite float exactly.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        let value = value.trunc();
+        if value.abs() < u64::MAX as f64 {
+            let magnitude = BigInt::from(value.abs() as u64);
+            return Some(if value < 0. { -magnitude } else { magnitude });
+        }
+        // Past 2^64 the float is its 53-bit mantissa shifted left.
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as u32 - 1075;
+        let mantissa = (bits & ((1 << 52) - 1)) | (1 << 52);
+        let mut magnitude = vec![0; (exponent / 32) as usize];
+        let shift = exponent % 32;
+        let shifted = (mantissa as u128) << shift;
+        magnitude.extend([
+            shifted as u32,
+            (shifted >> 32) as u32,
+            (shifted >> 64) as u32,
+        ]);
+        Some(BigInt::new(value < 0., magnitude))
+    }
+
+    /// Converts to the nearest float, which is infinite past its range.
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0f64, |acc, &limb| acc * 4294967296. + limb as f64);
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    /// The value as a `u32`, if it is one.
+    pub fn to_u32(&self) -> Option<u32> {
+        match (self.negative, self.magnitude.as_slice()) {
+            (false, []) => Some(0),
+            (false, [limb]) => Some(*limb),
+            _ => None,
+        }
+    }
+
+    /// The value as a `usize`, if it is one.
+    pub fn to_usize(&self) -> Option<usize> {
+        match (self.negative, self.magnitude.as_slice()) {
+            (false, []) => Some(0),
+            (false, [low]) => Some(*low as usize),
+            (false, [low, high]) => usize::try_from((*high as u64) << 32 | *low as u64).ok(),
+            _ => None,
+        }
+    }
+
+    /// The value as an `i64`, saturating at its bounds.
+    pub fn to_i64_saturating(&self) -> i64 {
+        let magnitude = match self.magnitude.as_slice() {
+            [] => 0,
+            [low] => *low as u64,
+            [low, high] => (*high as u64) << 32 | *low as u64,
+            _ => u64::MAX,
+        };
+        match self.negative {
+            true => 0i64.saturating_sub_unsigned(magnitude),
+            false => i64::try_from(magnitude).unwrap_or(i64::MAX),
+        }
+    }
+
+    /// Orders the integer against a float without rounding either of them.
+    pub fn compare_f64(&self, other: f64) -> Option<Ordering> {
+        if other.is_nan() {
+            return None;
+        }
+        if other.is_infinite() {
+            return Some(if other > 0. {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            });
+        }
+        match self.cmp(&BigInt::from_f64(other)?) {
+            Ordering::Equal => 0f64.partial_cmp(&other.fract()),
+            ordering => Some(ordering),
+        }
+    }
+
+    /// Divides, rounding the quotient towards negative infinity, so that the
+    /// remainder takes the sign of the divisor. Dividing by zero gives `None`.
+    pub fn div_rem_floor(&self, divisor: &BigInt) -> Option<(BigInt, BigInt)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = div_rem_magnitude(&self.magnitude, &divisor.magnitude);
+        let quotient = BigInt::new(self.negative != divisor.negative, quotient);
+        let remainder = BigInt::new(self.negative, remainder);
+        if !remainder.is_zero() && self.negative != divisor.negative {
+            Some((&quotient - &BigInt::from(1u64), &remainder + divisor))
+        } else {
+            Some((quotient, remainder))
+        }
+    }
+
+    /// How many bits the magnitude takes, zero having none.
+    fn bit_len(&self) -> u64 {
+        match self.magnitude.last() {
+            Some(top) => self.magnitude.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    /// Raises to a non-negative power, or gives `None` if the magnitude of
+    /// the result would take more than `max_bits` bits.
+    pub fn checked_pow(&self, exponent: &BigInt, max_bits: u64) -> Option<BigInt> {
+        if exponent.is_zero() {
+            return Some(BigInt::from(1u64));
+        }
+        if self.bit_len() <= 1 {
+            // Zero, one and minus one keep their magnitude, and minus one
+            // stays negative for odd exponents.
+            let odd = exponent.magnitude[0] & 1 == 1;
+            return Some(BigInt::new(self.negative && odd, self.magnitude.clone()));
+        }
+        // The result takes at least this many bits, as the base is at least
+        // 2^(bit_len - 1).
+        let exponent = exponent.to_u32()?;
+        if (self.bit_len() - 1) * exponent as u64 + 1 > max_bits {
+            return None;
+        }
+        Some(self.pow(exponent))
+    }
+
+    fn pow(&self, mut exponent: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::from(1u64);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(value: u64) -> Self {
+        BigInt::new(false, vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let magnitude = BigInt::from(value.unsigned_abs());
+        if value < 0 { -magnitude } else { magnitude }
+    }
+}
+
+fn compare_magnitude(m1: &[u32], m2: &[u32]) -> Ordering {
+    m1.len()
+        .cmp(&m2.len())
+        .then_with(|| m1.iter().rev().cmp(m2.iter().rev()))
+}
+
+fn add_magnitude(m1: &[u32], m2: &[u32]) -> Vec<u32> {
+    let (long, short) = if m1.len() >= m2.len() {
+        (m1, m2)
+    } else {
+        (m2, m1)
+    };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &limb) in long.iter().enumerate() {
+        let sum = limb as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// Subtracts the smaller magnitude `m2` from `m1`.
+fn sub_magnitude(m1: &[u32], m2: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(m1.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in m1.iter().enumerate() {
+        let mut diff = limb as i64 - *m2.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    while result.last() == Some(&0) {
+        result.pop();
+    }
+    result
+}
+
+fn mul_magnitude(m1: &[u32], m2: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; m1.len() + m2.len()];
+    for (i, &a) in m1.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &b) in m2.iter().enumerate() {
+            let value = a as u64 * b as u64 + result[i + j] as u64 + carry;
+            result[i + j] = value as u32;
+            carry = value >> 32;
+        }
+        result[i + m2.len()] = carry as u32;
+    }
+    result
+}
+
+/// Divides by a single digit, returning the quotient and the remainder.
+fn div_rem_digit(m: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; m.len()];
+    let mut remainder = 0u64;
+    for (i, &limb) in m.iter().enumerate().rev() {
+        let value = remainder << 32 | limb as u64;
+        quotient[i] = (value / divisor as u64) as u32;
+        remainder = value % divisor as u64;
+    }
+    (quotient, remainder as u32)
+}
+
+/// Long division one bit at a time, returning the truncated quotient and
+/// the remainder.
+fn div_rem_magnitude(m1: &[u32], m2: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = m2 {
+        let (quotient, remainder) = div_rem_digit(m1, *divisor);
+        return (quotient, vec![remainder]);
+    }
+    let mut quotient = vec![0u32; m1.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for bit in (0..m1.len() * 32).rev() {
+        let mut carry = (m1[bit / 32] >> (bit % 32)) & 1;
+        for limb in remainder.iter_mut() {
+            let shifted = *limb >> 31;
+            *limb = *limb << 1 | carry;
+            carry = shifted;
+        }
+        if carry > 0 {
+            remainder.push(carry);
+        }
+        if compare_magnitude(&remainder, m2).is_ge() {
+            remainder = sub_magnitude(&remainder, m2);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (quotient, remainder)
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(
+                self.negative,
+                add_magnitude(&self.magnitude, &other.magnitude),
+            );
+        }
+        match compare_magnitude(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::new(
+                other.negative,
+                sub_magnitude(&other.magnitude, &self.magnitude),
+            ),
+            _ => BigInt::new(
+                self.negative,
+                sub_magnitude(&self.magnitude, &other.magnitude),
+            ),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other.clone()
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_magnitude(&self.magnitude, &other.magnitude),
+        )
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+        let mut chunks = Vec::new();
+        let mut magnitude = self.magnitude.clone();
+        while !magnitude.is_empty() {
+            let (quotient, remainder) = div_rem_digit(&magnitude, CHUNK);
+            chunks.push(remainder);
+            magnitude = BigInt::new(false, quotient).magnitude;
+        }
+        let mut digits = String::new();
+        match chunks.split_last() {
+            Some((first, rest)) => {
+                digits.push_str(&first.to_string());
+                for chunk in rest.iter().rev() {
+                    digits.push_str(&format!("{:09}", chunk));
+                }
+            }
+            None => digits.push('0'),
+        }
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::BigInt;
+
+    /// Parses a decimal integer, which may start with '-'.
+    fn int(s: &str) -> BigInt {
+        match s.strip_prefix('-') {
+            Some(digits) => -BigInt::from_str_radix(digits, 10).unwrap(),
+            None => BigInt::from_str_radix(s, 10).unwrap(),
+        }
+    }
+
+    fn div_rem(dividend: &str, divisor: &str) -> (String, String) {
+        let (quotient, remainder) = int(dividend).div_rem_floor(&int(divisor)).unwrap();
+        (quotient.to_string(), remainder.to_string())
+    }
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!(int("0").to_string(), "0");
+        assert_eq!(int("-0").to_string(), "0");
+        assert_eq!(
+            int("1000000000000000000").to_string(),
+            "1000000000000000000"
+        );
+        assert_eq!(
+            int("-123456789012345678901234567890").to_string(),
+            "-123456789012345678901234567890"
+        );
+        assert_eq!(BigInt::from_str_radix("ff", 16), Some(int("255")));
+        assert_eq!(BigInt::from_str_radix("", 10), None);
+        assert_eq!(BigInt::from_str_radix("12a", 10), None);
+        assert_eq!(format!("{:>5}", int("-42")), "  -42");
+    }
+
+    #[test]
+    fn add_and_sub() {
+        assert_eq!(
+            (&BigInt::from(u64::MAX) + &int("1")).to_string(),
+            "18446744073709551616"
+        );
+        assert_eq!((&int("5") + &int("-8")).to_string(), "-3");
+        assert_eq!((&int("-5") - &int("-5")), BigInt::default());
+        assert!(!(&int("-5") - &int("-5")).is_negative());
+        assert_eq!(
+            (&int("18446744073709551616") - &int("1")).to_string(),
+            "18446744073709551615"
+        );
+    }
+
+    #[test]
+    fn mul() {
+        assert_eq!(
+            (&int("123456789") * &int("987654321")).to_string(),
+            "121932631112635269"
+        );
+        assert_eq!(
+            (&int("100000000000000000000") * &int("-100000000000000000000")).to_string(),
+            "-10000000000000000000000000000000000000000"
+        );
+        assert!(!(&int("-7") * &int("0")).is_negative());
+    }
+
+    #[test]
+    fn div_rem_floor_rounds_down() {
+        let pair = |q: &str, r: &str| (q.to_string(), r.to_string());
+        assert_eq!(div_rem("7", "2"), pair("3", "1"));
+        assert_eq!(div_rem("-7", "2"), pair("-4", "1"));
+        assert_eq!(div_rem("7", "-2"), pair("-4", "-1"));
+        assert_eq!(div_rem("-7", "-2"), pair("3", "-1"));
+        assert_eq!(div_rem("6", "-3"), pair("-2", "0"));
+        assert_eq!(
+            div_rem("1000000000000000000000000000007", "1000000000000000"),
+            pair("1000000000000000", "7")
+        );
+        assert_eq!(
+            div_rem("-1000000000000000000000000000007", "1000000000000000"),
+            pair("-1000000000000001", "999999999999993")
+        );
+        assert_eq!(
+            div_rem("-18446744073709551621", "1099511627779"),
+            pair("-16777216", "50331643")
+        );
+        assert_eq!(int("1").div_rem_floor(&int("0")), None);
+    }
+
+    #[test]
+    fn from_f64_truncates() {
+        assert_eq!(BigInt::from_f64(2.9), Some(int("2")));
+        assert_eq!(BigInt::from_f64(-2.9), Some(int("-2")));
+        assert_eq!(BigInt::from_f64(1e20), Some(int("100000000000000000000")));
+        assert_eq!(
+            BigInt::from_f64(-(2f64.powi(70))),
+            Some(int("-1180591620717411303424"))
+        );
+        assert_eq!(BigInt::from_f64(f64::NAN), None);
+        assert_eq!(BigInt::from_f64(f64::INFINITY), None);
+    }
+
+    #[test]
+    fn checked_pow_is_bounded() {
+        assert_eq!(
+            int("2").checked_pow(&int("100"), 1000),
+            Some(int("1267650600228229401496703205376"))
+        );
+        assert_eq!(int("0").checked_pow(&int("0"), 1), Some(int("1")));
+        assert_eq!(
+            int("-1").checked_pow(&int("100000000000000000001"), 1),
+            Some(int("-1"))
+        );
+        assert_eq!(int("2").checked_pow(&int("100"), 100), None);
+        assert_eq!(
+            int("2").checked_pow(&int("99"), 100),
+            Some(int("633825300114114700748351602688"))
+        );
+        assert_eq!(int("3").checked_pow(&int("10000000000"), 1 << 20), None);
+    }
+}
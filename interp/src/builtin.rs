@@ -0,0 +1,543 @@
+use std::cmp::Ordering;
+
+use crate::{
+    Evaluator,
+    bigint::BigInt,
+    diag::{Diag, DiagData, EvalError},
+    obj::{
+        BuiltinFn, BuiltinObj, DictObj, IntegerObj, ListObj, NumericalObj, OperationKind, RangeObj,
+        StringObj, TupleObj, ValueObj,
+    },
+    scope::Scope,
+};
+
+const BUILTINS: &[(&str, BuiltinFn)] = &[
+    ("in", print),
+    ("mảng", list),
+    ("khoảng", range),
+    ("độ dài", length),
+    ("thêm", append),
+    ("chèn", insert),
+    ("xóa", remove),
+    ("từ điển", dict),
+    ("lấy", get),
+    ("đặt", set),
+    ("khóa", keys),
+    ("giá trị", values),
+    ("cặp", pairs),
+];
+
+/// Methods of each type, which take the value they are called on as their
+/// first argument.
+const STRING_METHODS: &[(&str, BuiltinFn)] = &[
+    ("độ dài", length),
+    ("viết hoa", uppercase),
+    ("viết thường", lowercase),
+    ("bỏ khoảng trắng", trim),
+    ("tách", split),
+    ("nối", join),
+    ("thay thế", replace),
+    ("tìm", find),
+];
+const NUMBER_METHODS: &[(&str, BuiltinFn)] = &[
+    ("làm tròn", round),
+    ("làm tròn xuống", floor),
+    ("làm tròn lên", ceil),
+    ("trị tuyệt đối", abs),
+];
+const TUPLE_METHODS: &[(&str, BuiltinFn)] = &[("độ dài", length)];
+const LIST_METHODS: &[(&str, BuiltinFn)] = &[
+    ("độ dài", length),
+    ("thêm", append),
+    ("chèn", insert),
+    ("xóa", remove),
+    ("lấy ra", pop),
+    ("đảo ngược", reverse),
+    ("sắp xếp", sort),
+];
+const DICT_METHODS: &[(&str, BuiltinFn)] = &[
+    ("độ dài", length),
+    ("lấy", get),
+    ("đặt", set),
+    ("xóa", remove),
+    ("khóa", keys),
+    ("giá trị", values),
+    ("cặp", pairs),
+];
+
+/// Looks a method up in the table of the receiver's type.
+pub fn method(receiver: &ValueObj, name: &str) -> Option<BuiltinObj> {
+    let table = match receiver {
+        ValueObj::String(_) => STRING_METHODS,
+        ValueObj::Integer(_) | ValueObj::Numerical(_) => NUMBER_METHODS,
+        ValueObj::Tuple(_) => TUPLE_METHODS,
+        ValueObj::List(_) => LIST_METHODS,
+        ValueObj::Dict(_) => DICT_METHODS,
+        _ => return None,
+    };
+    table
+        .iter()
+        .find(|(method, _)| *method == name)
+        .map(|&(name, func)| BuiltinObj { name, func })
+}
+
+/// Declares every builtin function in `scope`.
+pub fn register(scope: &Scope) {
+    for &(name, func) in BUILTINS {
+        scope.declare(name, ValueObj::Builtin(BuiltinObj { name, func }));
+    }
+}
+
+fn expect_arity(
+    eval: &Evaluator,
+    name: &str,
+    args: &[ValueObj],
+    expected: usize,
+) -> Result<(), Diag> {
+    if args.len() == expected {
+        return Ok(());
+    }
+    Err(Diag {
+        line: eval.line(),
+        data: DiagData::EvalError(EvalError::ArityMismatch {
+            name: name.to_string(),
+            expected,
+            found: args.len(),
+        }),
+    })
+}
+
+fn mismatch(eval: &Evaluator, name: &str, expected: &str, found: &ValueObj) -> Diag {
+    Diag {
+        line: eval.line(),
+        data: DiagData::EvalError(EvalError::ArgumentMismatch {
+            name: name.to_string(),
+            expected: expected.to_string(),
+            found: found.describe(),
+        }),
+    }
+}
+
+fn expect_dict(eval: &Evaluator, name: &str, value: &ValueObj) -> Result<DictObj, Diag> {
+    match value {
+        ValueObj::Dict(d) => Ok(d.clone()),
+        _ => Err(mismatch(eval, name, "a dictionary", value)),
+    }
+}
+
+fn expect_list(eval: &Evaluator, name: &str, value: &ValueObj) -> Result<ListObj, Diag> {
+    match value {
+        ValueObj::List(l) => Ok(l.clone()),
+        _ => Err(mismatch(eval, name, "a list", value)),
+    }
+}
+
+fn expect_string(eval: &Evaluator, name: &str, value: &ValueObj) -> Result<StringObj, Diag> {
+    match value {
+        ValueObj::String(s) => Ok(s.clone()),
+        _ => Err(mismatch(eval, name, "a string", value)),
+    }
+}
+
+fn string(value: impl Into<std::rc::Rc<str>>) -> ValueObj {
+    ValueObj::String(StringObj(value.into()))
+}
+
+/// Prints its arguments separated by spaces.
+fn print(_eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    let words: Vec<String> = args.iter().map(ValueObj::to_string).collect();
+    println!("{}", words.join(" "));
+    Ok(ValueObj::Undefined)
+}
+
+/// Makes a new list, empty or holding the elements of an iterable.
+fn list(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    if args.is_empty() {
+        return Ok(ValueObj::List(ListObj::new(Vec::new())));
+    }
+    expect_arity(eval, "mảng", &args, 1)?;
+    Ok(ValueObj::List(ListObj::new(
+        args[0].iterate(eval)?.collect(),
+    )))
+}
+
+/// Makes a range of integers from a start, which defaults to zero, up to
+/// but excluding a stop, every step of them, which defaults to one.
+fn range(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    if !(1..=3).contains(&args.len()) {
+        expect_arity(eval, "khoảng", &args, 2)?;
+    }
+    let mut bounds = Vec::new();
+    for arg in &args {
+        match arg {
+            ValueObj::Integer(int) => bounds.push(int.0.clone()),
+            _ => return Err(mismatch(eval, "khoảng", "an integer", arg)),
+        }
+    }
+    let step = match bounds.len() {
+        3 => bounds.pop().unwrap_or_default(),
+        _ => BigInt::from(1u64),
+    };
+    if step.is_zero() {
+        return Err(Diag {
+            line: eval.line(),
+            data: DiagData::EvalError(EvalError::ZeroRangeStep),
+        });
+    }
+    let stop = bounds.pop().unwrap_or_default();
+    let start = bounds.pop().unwrap_or_default();
+    Ok(ValueObj::Range(RangeObj { start, stop, step }))
+}
+
+/// Counts the characters of a string, the elements of a tuple or list, or
+/// the entries of a dictionary.
+fn length(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "độ dài", &args, 1)?;
+    let len = match &args[0] {
+        ValueObj::String(s) => s.0.chars().count(),
+        ValueObj::Tuple(t) => t.0.len(),
+        ValueObj::List(l) => l.0.borrow().len(),
+        ValueObj::Dict(d) => d.0.borrow().len(),
+        value => {
+            return Err(mismatch(
+                eval,
+                "độ dài",
+                "a string, tuple, list or dictionary",
+                value,
+            ));
+        }
+    };
+    Ok(ValueObj::Integer(IntegerObj(BigInt::from(len as u64))))
+}
+
+/// Adds a value to the end of a list.
+fn append(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "thêm", &args, 2)?;
+    let list = expect_list(eval, "thêm", &args[0])?;
+    list.0.borrow_mut().push(args[1].clone());
+    Ok(ValueObj::Undefined)
+}
+
+/// Inserts a value before the given index of a list. A negative index
+/// counts from the end, and one past either end inserts at that end.
+fn insert(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "chèn", &args, 3)?;
+    let list = expect_list(eval, "chèn", &args[0])?;
+    let ValueObj::Integer(index) = &args[1] else {
+        return Err(mismatch(eval, "chèn", "an integer index", &args[1]));
+    };
+    let mut elements = list.0.borrow_mut();
+    let len = BigInt::from(elements.len() as u64);
+    let index = if index.0.is_negative() {
+        &len + &index.0
+    } else {
+        index.0.clone()
+    };
+    let index = match index.is_negative() {
+        true => 0,
+        false => index.to_usize().unwrap_or(usize::MAX).min(elements.len()),
+    };
+    elements.insert(index, args[2].clone());
+    Ok(ValueObj::Undefined)
+}
+
+/// Removes the first element of a list equal to a value, or a key and its
+/// value from a dictionary.
+fn remove(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "xóa", &args, 2)?;
+    if let ValueObj::Dict(dict) = &args[0] {
+        dict.remove(eval, &args[1])?;
+        return Ok(ValueObj::Undefined);
+    }
+    let list = expect_list(eval, "xóa", &args[0])?;
+    let position = list
+        .0
+        .borrow()
+        .iter()
+        .position(|element| *element == args[1]);
+    let Some(index) = position else {
+        return Err(Diag {
+            line: eval.line(),
+            data: DiagData::EvalError(EvalError::NotInList {
+                value: args[1].repr(),
+            }),
+        });
+    };
+    list.0.borrow_mut().remove(index);
+    Ok(ValueObj::Undefined)
+}
+
+/// Makes a new dictionary, empty or holding the key and value pairs of an
+/// iterable.
+fn dict(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    let dict = DictObj::new();
+    if args.is_empty() {
+        return Ok(ValueObj::Dict(dict));
+    }
+    expect_arity(eval, "từ điển", &args, 1)?;
+    for pair in args[0].iterate(eval)? {
+        let (key, value) = match &pair {
+            ValueObj::Tuple(t) if t.0.len() == 2 => (t.0[0].clone(), t.0[1].clone()),
+            _ => {
+                return Err(mismatch(
+                    eval,
+                    "từ điển",
+                    "pairs of a key and a value",
+                    &pair,
+                ));
+            }
+        };
+        dict.insert(eval, key, value)?;
+    }
+    Ok(ValueObj::Dict(dict))
+}
+
+/// Looks a key up in a dictionary, giving the default value if one is
+/// passed and the key is missing.
+fn get(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    if args.len() != 3 {
+        expect_arity(eval, "lấy", &args, 2)?;
+    }
+    let dict = expect_dict(eval, "lấy", &args[0])?;
+    match args.get(2) {
+        Some(default) => {
+            let key = args[1].hash_key(eval)?;
+            Ok(dict.0.borrow().get(&key).unwrap_or(default).clone())
+        }
+        None => dict.get(eval, &args[1]),
+    }
+}
+
+/// Sets the value of a key in a dictionary.
+fn set(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "đặt", &args, 3)?;
+    let dict = expect_dict(eval, "đặt", &args[0])?;
+    dict.insert(eval, args[1].clone(), args[2].clone())?;
+    Ok(ValueObj::Undefined)
+}
+
+/// Lists the keys of a dictionary in insertion order.
+fn keys(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "khóa", &args, 1)?;
+    let dict = expect_dict(eval, "khóa", &args[0])?;
+    let keys = dict
+        .0
+        .borrow()
+        .entries()
+        .iter()
+        .map(|(key, _)| key.clone())
+        .collect();
+    Ok(ValueObj::List(ListObj::new(keys)))
+}
+
+/// Lists the values of a dictionary in insertion order.
+fn values(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "giá trị", &args, 1)?;
+    let dict = expect_dict(eval, "giá trị", &args[0])?;
+    let values = dict
+        .0
+        .borrow()
+        .entries()
+        .iter()
+        .map(|(_, value)| value.clone())
+        .collect();
+    Ok(ValueObj::List(ListObj::new(values)))
+}
+
+/// Lists the entries of a dictionary in insertion order, each as a tuple
+/// of its key and value.
+fn pairs(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "cặp", &args, 1)?;
+    let dict = expect_dict(eval, "cặp", &args[0])?;
+    let pairs = dict
+        .0
+        .borrow()
+        .entries()
+        .iter()
+        .map(|(key, value)| ValueObj::Tuple(TupleObj(vec![key.clone(), value.clone()])))
+        .collect();
+    Ok(ValueObj::List(ListObj::new(pairs)))
+}
+
+/// Converts every letter of a string to upper case.
+fn uppercase(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "viết hoa", &args, 1)?;
+    let s = expect_string(eval, "viết hoa", &args[0])?;
+    Ok(string(s.0.to_uppercase()))
+}
+
+/// Converts every letter of a string to lower case.
+fn lowercase(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "viết thường", &args, 1)?;
+    let s = expect_string(eval, "viết thường", &args[0])?;
+    Ok(string(s.0.to_lowercase()))
+}
+
+/// Removes the whitespace at both ends of a string.
+fn trim(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "bỏ khoảng trắng", &args, 1)?;
+    let s = expect_string(eval, "bỏ khoảng trắng", &args[0])?;
+    Ok(string(s.0.trim()))
+}
+
+/// Splits a string into a list of strings around a separator, or around
+/// runs of whitespace if none is passed.
+fn split(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    if args.len() != 1 {
+        expect_arity(eval, "tách", &args, 2)?;
+    }
+    let s = expect_string(eval, "tách", &args[0])?;
+    let parts: Vec<ValueObj> = match args.get(1) {
+        None => s.0.split_whitespace().map(string).collect(),
+        Some(separator) => {
+            let separator = expect_string(eval, "tách", separator)?;
+            if separator.0.is_empty() {
+                return Err(mismatch(eval, "tách", "a non-empty separator", &args[1]));
+            }
+            s.0.split(&*separator.0).map(string).collect()
+        }
+    };
+    Ok(ValueObj::List(ListObj::new(parts)))
+}
+
+/// Joins the strings of an iterable, putting the separator it is called on
+/// between each of them.
+fn join(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "nối", &args, 2)?;
+    let separator = expect_string(eval, "nối", &args[0])?;
+    let mut parts = Vec::new();
+    for part in args[1].iterate(eval)? {
+        parts.push(expect_string(eval, "nối", &part)?.0);
+    }
+    Ok(string(parts.join(&separator.0)))
+}
+
+/// Replaces every occurrence of a string within a string.
+fn replace(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "thay thế", &args, 3)?;
+    let s = expect_string(eval, "thay thế", &args[0])?;
+    let from = expect_string(eval, "thay thế", &args[1])?;
+    let to = expect_string(eval, "thay thế", &args[2])?;
+    Ok(string(s.0.replace(&*from.0, &to.0)))
+}
+
+/// Gives the index of the first character where a string occurs within a
+/// string, or -1 if it does not.
+fn find(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "tìm", &args, 2)?;
+    let s = expect_string(eval, "tìm", &args[0])?;
+    let needle = expect_string(eval, "tìm", &args[1])?;
+    let index = match s.0.find(&*needle.0) {
+        Some(i) => BigInt::from(s.0[..i].chars().count() as u64),
+        None => BigInt::from(-1i64),
+    };
+    Ok(ValueObj::Integer(IntegerObj(index)))
+}
+
+/// Rounds a number to an integer with `rounding`, leaving integers as they
+/// are.
+fn round_with(
+    eval: &Evaluator,
+    name: &str,
+    args: &[ValueObj],
+    rounding: fn(f64) -> f64,
+) -> Result<ValueObj, Diag> {
+    expect_arity(eval, name, args, 1)?;
+    match &args[0] {
+        ValueObj::Integer(_) => Ok(args[0].clone()),
+        ValueObj::Numerical(num) => match BigInt::from_f64(rounding(num.0)) {
+            Some(int) => Ok(ValueObj::Integer(IntegerObj(int))),
+            None => Err(mismatch(eval, name, "a finite number", &args[0])),
+        },
+        value => Err(mismatch(eval, name, "a number", value)),
+    }
+}
+
+/// Rounds a number to the nearest integer, and halves to the even one.
+fn round(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    round_with(eval, "làm tròn", &args, f64::round_ties_even)
+}
+
+/// Rounds a number down to an integer.
+fn floor(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    round_with(eval, "làm tròn xuống", &args, f64::floor)
+}
+
+/// Rounds a number up to an integer.
+fn ceil(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    round_with(eval, "làm tròn lên", &args, f64::ceil)
+}
+
+/// Gives the absolute value of a number.
+fn abs(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "trị tuyệt đối", &args, 1)?;
+    match &args[0] {
+        ValueObj::Integer(int) if int.0.is_negative() => {
+            Ok(ValueObj::Integer(IntegerObj(-int.0.clone())))
+        }
+        ValueObj::Integer(_) => Ok(args[0].clone()),
+        ValueObj::Numerical(num) => Ok(ValueObj::Numerical(NumericalObj(num.0.abs()))),
+        value => Err(mismatch(eval, "trị tuyệt đối", "a number", value)),
+    }
+}
+
+/// Removes and gives the element of a list at an index, or its last
+/// element if no index is passed.
+fn pop(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    if args.len() != 1 {
+        expect_arity(eval, "lấy ra", &args, 2)?;
+    }
+    let list = expect_list(eval, "lấy ra", &args[0])?;
+    let index = match args.get(1) {
+        None => IntegerObj(BigInt::from(-1i64)),
+        Some(ValueObj::Integer(index)) => index.clone(),
+        Some(value) => return Err(mismatch(eval, "lấy ra", "an integer index", value)),
+    };
+    let len = list.0.borrow().len();
+    let index = index.resolve_index(eval, len)?;
+    Ok(list.0.borrow_mut().remove(index))
+}
+
+/// Reverses the order of the elements of a list in place.
+fn reverse(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "đảo ngược", &args, 1)?;
+    let list = expect_list(eval, "đảo ngược", &args[0])?;
+    list.0.borrow_mut().reverse();
+    Ok(ValueObj::Undefined)
+}
+
+/// Sorts the elements of a list in place, in ascending order. Elements
+/// that cannot be ordered against each other are an error, as with `<`.
+fn sort(eval: &mut Evaluator, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+    expect_arity(eval, "sắp xếp", &args, 1)?;
+    let list = expect_list(eval, "sắp xếp", &args[0])?;
+    // Elements may hold the list itself, so it cannot stay borrowed while
+    // they are compared.
+    let mut elements = list.0.borrow().clone();
+    let mut unordered = None;
+    elements.sort_by(|a, b| {
+        a.compare(b).unwrap_or_else(|| {
+            // NaN is the only number without an order, so it is named rather
+            // than reported as two numbers that cannot be compared.
+            let is_nan = |v: &ValueObj| matches!(v, ValueObj::Numerical(n) if n.0.is_nan());
+            unordered.get_or_insert_with(|| match is_nan(a) || is_nan(b) {
+                true => EvalError::UnorderedNaN,
+                false => EvalError::UndefinedOperation {
+                    op: OperationKind::Less,
+                    operand: format!("{} and {}", a.describe(), b.describe()),
+                },
+            });
+            Ordering::Equal
+        })
+    });
+    match unordered {
+        None => {
+            *list.0.borrow_mut() = elements;
+            Ok(ValueObj::Undefined)
+        }
+        Some(error) => Err(Diag {
+            line: eval.line(),
+            data: DiagData::EvalError(error),
+        }),
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::obj::OperationKind;
+use crate::obj::{OperationKind, ValueObj};
 
 pub struct Diag {
     pub line: u32,
@@ -16,6 +16,8 @@ impl Display for Diag {
 pub enum DiagData {
     ParseError(parser::diag::Diag),
     EvalError(EvalError),
+    /// A value thrown with 'ném', or an error caught and thrown again.
+    Thrown(ValueObj),
 }
 
 impl Display for DiagData {
@@ -23,15 +25,90 @@ impl Display for DiagData {
         match self {
             Self::ParseError(diag) => write!(f, "Parse error: {}", diag),
             Self::EvalError(err) => write!(f, "Evaluation error: {}", err),
+            Self::Thrown(ValueObj::Exception(exception)) => {
+                write!(f, "Evaluation error: {}", exception.message)
+            }
+            Self::Thrown(value) => write!(f, "Uncaught exception: {}", value.repr()),
         }
     }
 }
 
+pub enum Warning {
+    Shadowing { name: String },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Shadowing { name } => {
+                write!(f, "'{}' hides a name declared in an outer scope", name)
+            }
+        }
+    }
+}
+
+impl EvalError {
+    /// The kind a caught error exposes as `loại`. Programmes compare
+    /// against these names, so they are part of the language: they do not
+    /// follow the variants' names, and errors that a programme would handle
+    /// alike share one.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::MalformedLiteral { .. } => "malformed literal",
+            Self::NotFoundInScope { .. } | Self::SwallowedCallee { .. } => "undefined name",
+            Self::AlreadyDeclaredInScope { .. } => "redeclaration",
+            Self::UndeclaredAssignment { .. } => "undeclared assignment",
+            Self::UndefinedOperation { .. } => "unsupported operation",
+            Self::NotIterable { .. } => "not iterable",
+            Self::NotCallable { .. } => "not callable",
+            Self::ArityMismatch { .. } => "argument count",
+            Self::RecursionLimit { .. } => "recursion limit",
+            Self::DivisionByZero => "division by zero",
+            Self::IntegerTooLarge { .. } => "integer too large",
+            Self::StringTooLong { .. } => "string too long",
+            Self::ArgumentMismatch { .. } => "argument type",
+            Self::NotInList { .. } => "not in list",
+            Self::UnorderedNaN => "unordered",
+            Self::Unhashable { .. } => "unhashable",
+            Self::KeyNotFound { .. } => "missing key",
+            Self::IndexOutOfRange { .. } => "index out of range",
+            Self::ZeroSliceStep | Self::ZeroRangeStep => "zero step",
+            Self::NoSuchMember { .. } => "missing member",
+            Self::IndexNotAssignable { .. } | Self::MemberNotAssignable { .. } => "not assignable",
+            Self::NotAClass { .. } => "not a class",
+            Self::NonExhaustiveMatch { .. } => "no match",
+        }
+    }
+}
+
+#[rustfmt::skip]
 pub enum EvalError {
     MalformedLiteral { lit: String },
     NotFoundInScope { name: String },
+    SwallowedCallee { name: String, callee: String },
     AlreadyDeclaredInScope { name: String },
+    UndeclaredAssignment { name: String },
     UndefinedOperation { op: OperationKind, operand: String },
+    NotIterable { operand: String },
+    NotCallable { operand: String },
+    ArityMismatch { name: String, expected: usize, found: usize },
+    RecursionLimit { limit: usize },
+    DivisionByZero,
+    IntegerTooLarge { bits: u64 },
+    StringTooLong { bytes: usize },
+    ArgumentMismatch { name: String, expected: String, found: String },
+    NotInList { value: String },
+    UnorderedNaN,
+    Unhashable { operand: String },
+    KeyNotFound { key: String },
+    IndexOutOfRange { index: String, length: usize },
+    IndexNotAssignable { operand: String },
+    ZeroSliceStep,
+    ZeroRangeStep,
+    NoSuchMember { operand: String, name: String },
+    MemberNotAssignable { operand: String, name: String },
+    NotAClass { operand: String },
+    NonExhaustiveMatch { value: String },
 }
 
 impl Display for EvalError {
@@ -43,12 +120,103 @@ impl Display for EvalError {
             Self::NotFoundInScope { name } => {
                 write!(f, "Identifier '{}' is not found in scope", name)
             }
+            Self::SwallowedCallee { name, callee } => {
+                write!(
+                    f,
+                    "Identifier '{}' is not found in scope; to call '{}', put its arguments in parentheses",
+                    name, callee
+                )
+            }
             Self::AlreadyDeclaredInScope { name } => {
                 write!(f, "Identifier '{}' is already declared in scope", name)
             }
+            Self::UndeclaredAssignment { name } => {
+                write!(
+                    f,
+                    "Cannot assign to '{}' because it has not been declared with 'cho'",
+                    name
+                )
+            }
             Self::UndefinedOperation { op, operand } => {
                 write!(f, "Undefined operation: {} with {}", op, operand)
             }
+            Self::NotIterable { operand } => {
+                write!(f, "Cannot iterate over {}", operand)
+            }
+            Self::NotCallable { operand } => {
+                write!(f, "Cannot call {}", operand)
+            }
+            Self::ArityMismatch {
+                name,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "Function '{}' takes {} argument(s) but {} were given",
+                    name, expected, found
+                )
+            }
+            Self::RecursionLimit { limit } => {
+                write!(f, "Maximum recursion depth of {} exceeded", limit)
+            }
+            Self::DivisionByZero => write!(f, "Division by zero"),
+            Self::IntegerTooLarge { bits } => {
+                write!(f, "The integer would take more than {} bits", bits)
+            }
+            Self::StringTooLong { bytes } => {
+                write!(f, "The string would take more than {} bytes", bytes)
+            }
+            Self::ArgumentMismatch {
+                name,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "Function '{}' expects {} but was given {}",
+                    name, expected, found
+                )
+            }
+            Self::NotInList { value } => {
+                write!(f, "{} is not in the list", value)
+            }
+            Self::UnorderedNaN => {
+                write!(
+                    f,
+                    "Cannot sort NaN, which is not ordered against any number"
+                )
+            }
+            Self::Unhashable { operand } => {
+                write!(f, "Cannot use {} as a dictionary key", operand)
+            }
+            Self::KeyNotFound { key } => {
+                write!(f, "Key {} is not in the dictionary", key)
+            }
+            Self::IndexOutOfRange { index, length } => {
+                write!(
+                    f,
+                    "Index {} is out of range for a length of {}",
+                    index, length
+                )
+            }
+            Self::IndexNotAssignable { operand } => {
+                write!(f, "Cannot assign through an index of {}", operand)
+            }
+            Self::ZeroSliceStep => write!(f, "A slice cannot have a step of zero"),
+            Self::ZeroRangeStep => write!(f, "A range cannot have a step of zero"),
+            Self::NoSuchMember { operand, name } => {
+                write!(f, "There is no member '{}' on {}", name, operand)
+            }
+            Self::MemberNotAssignable { operand, name } => {
+                write!(f, "Cannot assign to member '{}' of {}", name, operand)
+            }
+            Self::NotAClass { operand } => {
+                write!(f, "Cannot inherit from {}, which is not a class", operand)
+            }
+            Self::NonExhaustiveMatch { value } => {
+                write!(f, "No case matches the value {}", value)
+            }
         }
     }
 }
@@ -1,79 +1,280 @@
+use std::rc::Rc;
+
 use parser::syntax::expr;
 
 use crate::{
     Evaluator,
+    bigint::BigInt,
+    builtin,
     diag::{Diag, DiagData, EvalError},
-    obj::{NumericalObj, Operation, OperationKind, TupleObj, ValueObj},
+    obj::{
+        BooleanObj, DictObj, IntegerObj, ListObj, MethodFn, MethodObj, NumericalObj, Operation,
+        OperationKind, StringObj, TupleObj, ValueObj,
+    },
 };
 
 pub trait Evaluable {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag>;
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag>;
 }
 
 impl Evaluable for expr::Expr {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
-        self.0.evaluate(eval)
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        eval.at_line(self.line, |eval| self.or.evaluate(eval))
+    }
+}
+
+impl Evaluable for expr::OrExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let (last, init) = self.0.split_last().expect("'hoặc' has an operand");
+        for operand in init {
+            let value = operand.evaluate(eval)?;
+            if value.is_truthy() {
+                return Ok(value);
+            }
+        }
+        last.evaluate(eval)
+    }
+}
+
+impl Evaluable for expr::AndExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let (last, init) = self.0.split_last().expect("'và' has an operand");
+        for operand in init {
+            let value = operand.evaluate(eval)?;
+            if !value.is_truthy() {
+                return Ok(value);
+            }
+        }
+        last.evaluate(eval)
+    }
+}
+
+impl Evaluable for expr::NotExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        match self {
+            expr::NotExpr::Not(operand) => Ok(ValueObj::Boolean(BooleanObj(
+                !operand.evaluate(eval)?.is_truthy(),
+            ))),
+            expr::NotExpr::Comparison(comparison) => comparison.evaluate(eval),
+        }
+    }
+}
+
+impl Evaluable for expr::ComparisonExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let mut lhs = self.first.evaluate(eval)?;
+        if self.rest.is_empty() {
+            return Ok(lhs);
+        }
+        for (kind, line, rhs) in &self.rest {
+            let rhs = rhs.evaluate(eval)?;
+            let operation = Operation {
+                kind: match kind {
+                    expr::ComparisonKind::Equal => OperationKind::Equal,
+                    expr::ComparisonKind::NotEqual => OperationKind::NotEqual,
+                    expr::ComparisonKind::Less => OperationKind::Less,
+                    expr::ComparisonKind::LessEqual => OperationKind::LessEqual,
+                    expr::ComparisonKind::Greater => OperationKind::Greater,
+                    expr::ComparisonKind::GreaterEqual => OperationKind::GreaterEqual,
+                    expr::ComparisonKind::In => OperationKind::In,
+                },
+                operands: (lhs, rhs.clone()),
+            };
+            let holds = eval.at_line(*line, |eval| operation.evaluate(eval))?;
+            if !holds.is_truthy() {
+                return Ok(holds);
+            }
+            lhs = rhs;
+        }
+        Ok(ValueObj::Boolean(BooleanObj(true)))
     }
 }
 
 impl Evaluable for expr::AddAffixedExpr {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
         match &self.lhs {
-            Some(op1) => Operation {
-                kind: if self.rhs.0 {
-                    OperationKind::Add
-                } else {
-                    OperationKind::Subtract
-                },
-                operands: (op1.evaluate(eval)?, self.rhs.1.evaluate(eval)?),
+            Some(op1) => {
+                let operation = Operation {
+                    kind: if self.rhs.0 {
+                        OperationKind::Add
+                    } else {
+                        OperationKind::Subtract
+                    },
+                    operands: (op1.evaluate(eval)?, self.rhs.1.evaluate(eval)?),
+                };
+                eval.at_line(self.line, |eval| operation.evaluate(eval))
             }
-            .evaluate(eval),
             None => self.rhs.1.evaluate(eval),
         }
     }
 }
 
 impl Evaluable for expr::MulAffixedExpr {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
         match &self.lhs {
-            Some(op1) => Operation {
-                kind: if self.rhs.0 {
-                    OperationKind::Multiply
-                } else {
-                    OperationKind::Divide
-                },
-                operands: (op1.evaluate(eval)?, self.rhs.1.evaluate(eval)?),
+            Some(op1) => {
+                let operation = Operation {
+                    kind: match self.rhs.0 {
+                        expr::MulKind::Multiply => OperationKind::Multiply,
+                        expr::MulKind::Divide => OperationKind::Divide,
+                        expr::MulKind::FloorDivide => OperationKind::FloorDivide,
+                        expr::MulKind::Modulo => OperationKind::Modulo,
+                    },
+                    operands: (op1.evaluate(eval)?, self.rhs.1.evaluate(eval)?),
+                };
+                eval.at_line(self.line, |eval| operation.evaluate(eval))
             }
-            .evaluate(eval),
             None => self.rhs.1.evaluate(eval),
         }
     }
 }
 
 impl Evaluable for expr::PrefixedExpr {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
         match &self.prefix {
-            Some(expr::PrefixedExprKind::Minus) => Operation {
-                kind: OperationKind::NegativePrefix,
-                operands: (ValueObj::Undefined, self.terminal.evaluate(eval)?),
+            Some(expr::PrefixedExprKind::Minus) => {
+                let operation = Operation {
+                    kind: OperationKind::NegativePrefix,
+                    operands: (ValueObj::Undefined, self.power.evaluate(eval)?),
+                };
+                eval.at_line(self.line, |eval| operation.evaluate(eval))
             }
-            .evaluate(eval),
-            None => self.terminal.evaluate(eval),
+            None => self.power.evaluate(eval),
         }
     }
 }
 
+impl Evaluable for expr::PowerExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        match &self.exponent {
+            Some(exponent) => {
+                let operation = Operation {
+                    kind: OperationKind::Power,
+                    operands: (self.base.evaluate(eval)?, exponent.evaluate(eval)?),
+                };
+                eval.at_line(self.line, |eval| operation.evaluate(eval))
+            }
+            None => self.base.evaluate(eval),
+        }
+    }
+}
+
+impl Evaluable for expr::PostfixedExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let mut value = self.terminal.evaluate(eval)?;
+        for (line, postfix) in &self.postfixes {
+            value = match postfix {
+                expr::Postfix::Call(tuple) => {
+                    let args = evaluate_args(tuple, eval)?;
+                    eval.at_line(*line, |eval| eval.call(value, args))?
+                }
+                expr::Postfix::Subscript(expr::Subscript::Index(index)) => {
+                    let operation = Operation {
+                        kind: OperationKind::Index,
+                        operands: (value, index.evaluate(eval)?),
+                    };
+                    eval.at_line(*line, |eval| operation.evaluate(eval))?
+                }
+                expr::Postfix::Subscript(expr::Subscript::Slice { start, stop, step }) => {
+                    let mut bound = |expr: &Option<expr::Expr>| match expr {
+                        Some(expr) => expr.evaluate(eval),
+                        None => Ok(ValueObj::Undefined),
+                    };
+                    let (start, stop, step) = (bound(start)?, bound(stop)?, bound(step)?);
+                    eval.at_line(*line, |eval| value.slice(eval, &start, &stop, &step))?
+                }
+                expr::Postfix::Member(member) => {
+                    eval.at_line(*line, |eval| get_member(eval, value, member))?
+                }
+            };
+        }
+        Ok(value)
+    }
+}
+
+/// Looks a member up on a value: a field or method of an instance, a
+/// method of a class, which is then called with the instance as its first
+/// argument, or a builtin method.
+pub(crate) fn get_member(
+    eval: &Evaluator,
+    value: ValueObj,
+    member: &expr::terminal::Ident,
+) -> Result<ValueObj, Diag> {
+    let name = eval.snippet(&member.0);
+    let found = match &value {
+        ValueObj::Instance(instance) => match instance.fields.borrow().get(name) {
+            Some(field) => Some(field.clone()),
+            None => instance.class.method(name).map(|method| {
+                ValueObj::Method(MethodObj {
+                    receiver: Box::new(value.clone()),
+                    func: MethodFn::Closure(method),
+                })
+            }),
+        },
+        ValueObj::Class(class) => class.method(name).map(ValueObj::Function),
+        ValueObj::Exception(exception) => match name {
+            "loại" => Some(ValueObj::String(StringObj(Rc::clone(&exception.kind)))),
+            "thông điệp" => Some(ValueObj::String(StringObj(Rc::clone(&exception.message)))),
+            _ => None,
+        },
+        _ => builtin::method(&value, name).map(|method| {
+            ValueObj::Method(MethodObj {
+                receiver: Box::new(value.clone()),
+                func: MethodFn::Builtin(method),
+            })
+        }),
+    };
+    found.ok_or_else(|| Diag {
+        line: eval.line(),
+        data: DiagData::EvalError(EvalError::NoSuchMember {
+            operand: value.describe(),
+            name: name.to_string(),
+        }),
+    })
+}
+
+/// Evaluates each argument of a call, unlike a tuple expression which
+/// collapses a single element into its value.
+pub(crate) fn evaluate_args(
+    tuple: &expr::TupleExpr,
+    eval: &mut Evaluator,
+) -> Result<Vec<ValueObj>, Diag> {
+    let mut args = Vec::new();
+    for expr in &tuple.0 {
+        args.push(expr.evaluate(eval)?);
+    }
+    Ok(args)
+}
+
 impl Evaluable for expr::TerminalExpr {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
         match self {
             expr::TerminalExpr::Literal(lit) => lit.evaluate(eval),
             expr::TerminalExpr::Tuple(lit) => lit.evaluate(eval),
+            expr::TerminalExpr::List(list) => list.evaluate(eval),
+            expr::TerminalExpr::Dict(dict) => dict.evaluate(eval),
+            expr::TerminalExpr::FString(fstring) => fstring.evaluate(eval),
         }
     }
 }
 
+impl Evaluable for expr::FStringExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let mut result = String::new();
+        for segment in &self.0 {
+            match segment {
+                expr::FStringSegment::Text(text) => result.push_str(text),
+                expr::FStringSegment::Expr(expr) => {
+                    result.push_str(&expr.evaluate(eval)?.to_string());
+                }
+            }
+        }
+        Ok(ValueObj::String(StringObj(result.into())))
+    }
+}
+
 impl Evaluable for expr::TupleExpr {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
         match self.0.len() {
             1 => Ok(self.0[0].evaluate(eval)?),
             _ => {
@@ -88,90 +289,107 @@ impl Evaluable for expr::TupleExpr {
     }
 }
 
+impl Evaluable for expr::ListExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let mut elements = Vec::new();
+        for expr in &self.0 {
+            elements.push(expr.evaluate(eval)?);
+        }
+        Ok(ValueObj::List(ListObj::new(elements)))
+    }
+}
+
+impl Evaluable for expr::DictExpr {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let dict = DictObj::new();
+        for (key, value) in &self.0 {
+            let key = key.evaluate(eval)?;
+            let value = value.evaluate(eval)?;
+            dict.insert(eval, key, value)?;
+        }
+        Ok(ValueObj::Dict(dict))
+    }
+}
+
 impl Evaluable for expr::terminal::Literal {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
         match self {
             expr::terminal::Literal::Decimal(lit) => lit.evaluate(eval),
             expr::terminal::Literal::Float(lit) => lit.evaluate(eval),
-            expr::terminal::Literal::Ident(lit) => match eval.global.get(eval.snippet(&lit.0)) {
-                Some(val) => Ok(val.clone()),
-                None => Err(Diag {
-                    line: eval.cur_line(),
+            expr::terminal::Literal::Ident(lit) => lit.evaluate(eval),
+            expr::terminal::Literal::Boolean(lit) => Ok(ValueObj::Boolean(BooleanObj(lit.0))),
+            expr::terminal::Literal::SelfRef(_) => {
+                let name = expr::terminal::Keyword::BanThan.as_str();
+                eval.lookup(name).ok_or_else(|| Diag {
+                    line: eval.line(),
                     data: DiagData::EvalError(EvalError::NotFoundInScope {
-                        name: eval.snippet(&lit.0).to_string(),
+                        name: name.to_string(),
                     }),
-                }),
-            },
-            expr::terminal::Literal::DoubleQuotedString(_) => unimplemented!(),
+                })
+            }
+            expr::terminal::Literal::QuotedString(lit) => lit.evaluate(eval),
         }
     }
 }
 
-impl Evaluable for expr::terminal::Decimal {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
-        let lit = eval.parser.get_snippet(&self.0);
-        let mut value = 0f64;
-        for (i, c) in lit.chars().rev().enumerate() {
-            if c == '_' {
-                continue;
-            }
-            let Some(digit) = c.to_digit(10) else {
-                return Err(Diag {
-                    line: eval.cur_line(),
-                    data: DiagData::EvalError(EvalError::MalformedLiteral {
-                        lit: lit.to_string(),
-                    }),
-                });
-            };
-            value += (digit as f64) * 10f64.powi(i as i32);
+impl Evaluable for expr::terminal::Ident {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let name = eval.snippet(&self.0);
+        match eval.lookup(name) {
+            Some(val) => Ok(val),
+            None => Err(Diag {
+                line: eval.line(),
+                data: DiagData::EvalError(undefined_ident(eval, name)),
+            }),
         }
-        Ok(ValueObj::Numerical(NumericalObj(value)))
     }
 }
 
-impl Evaluable for expr::terminal::Float {
-    fn evaluate(&self, interpreter: &Evaluator) -> Result<ValueObj, Diag> {
-        let lit = interpreter.parser.get_snippet(&self.0);
-        let mut value = 0f64;
-        let parts: Vec<&str> = lit.split('.').collect();
-        if parts.len() != 2 {
-            return Err(Diag {
-                line: interpreter.cur_line(),
+/// Explains a missing multi-word identifier whose leading words name a
+/// function, as `in bình phương(3)` reads as a call of `in bình phương`.
+fn undefined_ident(eval: &Evaluator, name: &str) -> EvalError {
+    let callee = name
+        .match_indices(' ')
+        .map(|(i, _)| name[..i].trim_end())
+        .rfind(|prefix| {
+            matches!(
+                eval.lookup(prefix),
+                Some(ValueObj::Function(_) | ValueObj::Builtin(_) | ValueObj::Class(_))
+            )
+        });
+    match callee {
+        Some(callee) => EvalError::SwallowedCallee {
+            name: name.to_string(),
+            callee: callee.to_string(),
+        },
+        None => EvalError::NotFoundInScope {
+            name: name.to_string(),
+        },
+    }
+}
+
+impl Evaluable for expr::terminal::QuotedString {
+    fn evaluate(&self, _eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        Ok(ValueObj::String(StringObj(Rc::clone(&self.1))))
+    }
+}
+
+impl Evaluable for expr::terminal::Decimal {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        match BigInt::from_str_radix(&self.2, self.1) {
+            Some(value) => Ok(ValueObj::Integer(IntegerObj(value))),
+            None => Err(Diag {
+                line: eval.line(),
                 data: DiagData::EvalError(EvalError::MalformedLiteral {
-                    lit: lit.to_string(),
+                    lit: eval.snippet(&self.0).to_string(),
                 }),
-            });
+            }),
         }
-        let int_part = parts[0];
-        let frac_part = parts[1];
-        for (i, c) in int_part.chars().rev().enumerate() {
-            if c == '_' {
-                continue;
-            }
-            let Some(digit) = c.to_digit(10) else {
-                return Err(Diag {
-                    line: interpreter.cur_line(),
-                    data: DiagData::EvalError(EvalError::MalformedLiteral {
-                        lit: lit.to_string(),
-                    }),
-                });
-            };
-            value += (digit as f64) * 10f64.powi(i as i32);
-        }
-        for (i, c) in frac_part.chars().enumerate() {
-            if c == '_' {
-                continue;
-            }
-            let Some(digit) = c.to_digit(10) else {
-                return Err(Diag {
-                    line: interpreter.cur_line(),
-                    data: DiagData::EvalError(EvalError::MalformedLiteral {
-                        lit: lit.to_string(),
-                    }),
-                });
-            };
-            value += (digit as f64) * 10f64.powi(-(i as i32 + 1));
-        }
-        Ok(ValueObj::Numerical(NumericalObj(value)))
+    }
+}
+
+impl Evaluable for expr::terminal::Float {
+    fn evaluate(&self, _eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        Ok(ValueObj::Numerical(NumericalObj(self.1)))
     }
 }
@@ -1,50 +1,455 @@
-use parser::syntax::stmt::{self};
+use std::{collections::HashMap, rc::Rc};
+
+use parser::syntax::{
+    pattern::Pattern,
+    stmt::{self},
+};
 
 use crate::{
     Evaluator,
     diag::{Diag, DiagData, EvalError},
-    eval::Evaluable,
-    obj::ValueObj,
+    eval::{Evaluable, get_member},
+    obj::{
+        Class, ClassObj, ClosureObj, ExceptionObj, ListObj, Operation, OperationKind, TupleObj,
+        ValueObj, assign_index,
+    },
+    scope::Scope,
 };
 
+/// How control leaves a statement once it has been interpreted.
+pub enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(ValueObj),
+}
+
 pub trait Interpretable {
-    fn interpret(&self, interpreter: &mut Evaluator) -> Result<(), Diag>;
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag>;
 }
 
 impl Interpretable for stmt::Statement {
-    fn interpret(&self, interpreter: &mut Evaluator) -> Result<(), Diag> {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        interpreter.at_line(self.line, |interpreter| self.kind.interpret(interpreter))
+    }
+}
+
+impl Interpretable for stmt::StatementKind {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
         match self {
-            stmt::Statement::Invocation(invocation_stmt) => invocation_stmt.interpret(interpreter),
-            stmt::Statement::Cho(cho_stmt) => cho_stmt.interpret(interpreter),
+            stmt::StatementKind::Invocation(invocation_stmt) => {
+                invocation_stmt.interpret(interpreter)
+            }
+            stmt::StatementKind::Cho(cho_stmt) => cho_stmt.interpret(interpreter),
+            stmt::StatementKind::Neu(neu_stmt) => neu_stmt.interpret(interpreter),
+            stmt::StatementKind::TrongKhi(trong_khi_stmt) => trong_khi_stmt.interpret(interpreter),
+            stmt::StatementKind::VoiMoi(voi_moi_stmt) => voi_moi_stmt.interpret(interpreter),
+            stmt::StatementKind::Dung(_) => Ok(Flow::Break),
+            stmt::StatementKind::TiepTuc(_) => Ok(Flow::Continue),
+            stmt::StatementKind::Ham(ham_stmt) => ham_stmt.interpret(interpreter),
+            stmt::StatementKind::TraVe(tra_ve_stmt) => tra_ve_stmt.interpret(interpreter),
+            stmt::StatementKind::Assignment(assign_stmt) => assign_stmt.interpret(interpreter),
+            stmt::StatementKind::Lop(lop_stmt) => lop_stmt.interpret(interpreter),
+            stmt::StatementKind::Thu(thu_stmt) => thu_stmt.interpret(interpreter),
+            stmt::StatementKind::Nem(nem_stmt) => nem_stmt.interpret(interpreter),
+            stmt::StatementKind::SoKhop(so_khop_stmt) => so_khop_stmt.interpret(interpreter),
+        }
+    }
+}
+
+impl Interpretable for stmt::Block {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        for stmt in &self.statements {
+            match stmt.interpret(interpreter)? {
+                Flow::Normal => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Normal)
+    }
+}
+
+/// Interprets a nested block in its own scope, so that names declared in
+/// it are gone once it ends. Loops make a new scope for every iteration.
+fn interpret_nested(
+    block: &stmt::Block,
+    interpreter: &mut Evaluator,
+    scope: Scope,
+) -> Result<Flow, Diag> {
+    interpreter.with_scope(scope, |interpreter| block.interpret(interpreter))
+}
+
+impl Interpretable for stmt::NeuStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        for (cond, block) in &self.branches {
+            if cond.evaluate(interpreter)?.is_truthy() {
+                return interpret_nested(block, interpreter, Scope::child(interpreter.scope()));
+            }
+        }
+        match &self.otherwise {
+            Some(block) => interpret_nested(block, interpreter, Scope::child(interpreter.scope())),
+            None => Ok(Flow::Normal),
         }
     }
 }
 
 impl Interpretable for stmt::InvocationStatement {
-    fn interpret(&self, _interpreter: &mut Evaluator) -> Result<(), Diag> {
-        // For now, just print out the invocation statement
-        println!("Interpreting invocation: {:?}", self);
-        Ok(())
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        self.call.evaluate(interpreter)?;
+        Ok(Flow::Normal)
     }
 }
 
 impl Interpretable for stmt::ChoStatement {
-    fn interpret(&self, interpreter: &mut Evaluator) -> Result<(), Diag> {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
         let value = match &self.rhs {
             Some(expr) => expr.evaluate(interpreter)?,
             None => ValueObj::Undefined,
         };
-        match interpreter
-            .global
-            .declare(interpreter.snippet(&self.lhs.0), value)
+        interpreter.declare(interpreter.snippet(&self.lhs.0), value)?;
+        Ok(Flow::Normal)
+    }
+}
+
+impl Interpretable for stmt::AssignmentStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        let op = match self.kind {
+            stmt::AssignmentKind::Plain => None,
+            stmt::AssignmentKind::Add => Some(OperationKind::Add),
+            stmt::AssignmentKind::Subtract => Some(OperationKind::Subtract),
+            stmt::AssignmentKind::Multiply => Some(OperationKind::Multiply),
+            stmt::AssignmentKind::Divide => Some(OperationKind::Divide),
+        };
+        let ident = match &self.lhs {
+            stmt::AssignmentTarget::Name(ident) => ident,
+            stmt::AssignmentTarget::Index { container, index } => {
+                // The container and the index are evaluated once, even when
+                // the element is also read, as in `a[i] += 1`.
+                let container = container.evaluate(interpreter)?;
+                let index = index.evaluate(interpreter)?;
+                let rhs = self.rhs.evaluate(interpreter)?;
+                let value = match op {
+                    None => rhs,
+                    Some(kind) => {
+                        let lhs = Operation {
+                            kind: OperationKind::Index,
+                            operands: (container.clone(), index.clone()),
+                        }
+                        .evaluate(interpreter)?;
+                        Operation {
+                            kind,
+                            operands: (lhs, rhs),
+                        }
+                        .evaluate(interpreter)?
+                    }
+                };
+                assign_index(interpreter, &container, index, value)?;
+                return Ok(Flow::Normal);
+            }
+            stmt::AssignmentTarget::Member { object, member } => {
+                let object = object.evaluate(interpreter)?;
+                let rhs = self.rhs.evaluate(interpreter)?;
+                let value = match op {
+                    None => rhs,
+                    Some(kind) => Operation {
+                        kind,
+                        operands: (get_member(interpreter, object.clone(), member)?, rhs),
+                    }
+                    .evaluate(interpreter)?,
+                };
+                let name = interpreter.snippet(&member.0);
+                let ValueObj::Instance(instance) = &object else {
+                    return Err(Diag {
+                        line: interpreter.line(),
+                        data: DiagData::EvalError(EvalError::MemberNotAssignable {
+                            operand: object.describe(),
+                            name: name.to_string(),
+                        }),
+                    });
+                };
+                instance.fields.borrow_mut().insert(name.into(), value);
+                return Ok(Flow::Normal);
+            }
+        };
+        let name = interpreter.snippet(&ident.0);
+        let undeclared = |interpreter: &Evaluator| Diag {
+            line: interpreter.line(),
+            data: DiagData::EvalError(EvalError::UndeclaredAssignment {
+                name: name.to_string(),
+            }),
+        };
+        let rhs = self.rhs.evaluate(interpreter)?;
+        let value = match op {
+            None => rhs,
+            Some(kind) => {
+                let Some(lhs) = interpreter.lookup(name) else {
+                    return Err(undeclared(interpreter));
+                };
+                Operation {
+                    kind,
+                    operands: (lhs, rhs),
+                }
+                .evaluate(interpreter)?
+            }
+        };
+        match interpreter.assign(name, value) {
+            true => Ok(Flow::Normal),
+            false => Err(undeclared(interpreter)),
+        }
+    }
+}
+
+impl Interpretable for stmt::TrongKhiStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        while self.cond.evaluate(interpreter)?.is_truthy() {
+            let scope = Scope::child(interpreter.scope());
+            match interpret_nested(&self.body, interpreter, scope)? {
+                Flow::Break => break,
+                Flow::Normal | Flow::Continue => {}
+                flow @ Flow::Return(_) => return Ok(flow),
+            }
+        }
+        Ok(Flow::Normal)
+    }
+}
+
+impl Interpretable for stmt::VoiMoiStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        let name = interpreter.snippet(&self.var.0);
+        for value in self.iterable.evaluate(interpreter)?.iterate(interpreter)? {
+            let scope = Scope::child(interpreter.scope());
+            scope.bind(name, value);
+            match interpret_nested(&self.body, interpreter, scope)? {
+                Flow::Break => break,
+                Flow::Normal | Flow::Continue => {}
+                flow @ Flow::Return(_) => return Ok(flow),
+            }
+        }
+        Ok(Flow::Normal)
+    }
+}
+
+impl Interpretable for Rc<stmt::HamStatement> {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        let name = interpreter.snippet(&self.name.0);
+        let func = ValueObj::Function(ClosureObj {
+            name: name.to_string(),
+            def: Rc::clone(self),
+            env: interpreter.scope().clone(),
+            is_method: false,
+        });
+        interpreter.declare(name, func)?;
+        Ok(Flow::Normal)
+    }
+}
+
+impl Interpretable for stmt::LopStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        let parent = match &self.parent {
+            Some(ident) => match ident.evaluate(interpreter)? {
+                ValueObj::Class(class) => Some(class),
+                value => {
+                    return Err(Diag {
+                        line: interpreter.line(),
+                        data: DiagData::EvalError(EvalError::NotAClass {
+                            operand: value.describe(),
+                        }),
+                    });
+                }
+            },
+            None => None,
+        };
+        let mut methods = HashMap::new();
+        for def in &self.methods {
+            let name = interpreter.snippet(&def.name.0);
+            let method = ClosureObj {
+                name: name.to_string(),
+                def: Rc::clone(def),
+                env: interpreter.scope().clone(),
+                is_method: true,
+            };
+            methods.insert(name.into(), method);
+        }
+        let name = interpreter.snippet(&self.name.0);
+        let class = ValueObj::Class(ClassObj(Rc::new(Class {
+            name: name.to_string(),
+            parent,
+            methods,
+        })));
+        interpreter.declare(name, class)?;
+        Ok(Flow::Normal)
+    }
+}
+
+impl Interpretable for stmt::ThuStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        let mut result =
+            interpret_nested(&self.body, interpreter, Scope::child(interpreter.scope()));
+        if let Some((name, handler)) = &self.handler
+            && let Err(diag) = result
         {
-            true => Ok(()),
-            false => Err(Diag {
-                line: interpreter.cur_line(),
-                data: DiagData::EvalError(EvalError::AlreadyDeclaredInScope {
-                    name: interpreter.snippet(&self.lhs.0).to_string(),
-                }),
+            result = match caught_value(diag) {
+                Ok(exception) => {
+                    let scope = Scope::child(interpreter.scope());
+                    if let Some(name) = name {
+                        scope.bind(interpreter.snippet(&name.0), exception);
+                    }
+                    interpret_nested(handler, interpreter, scope)
+                }
+                Err(diag) => Err(diag),
+            };
+        }
+        if let Some(finally) = &self.finally {
+            // Leaving the 'cuối cùng' block early overrides how the rest of
+            // the statement ended.
+            match interpret_nested(finally, interpreter, Scope::child(interpreter.scope()))? {
+                Flow::Normal => {}
+                flow => return Ok(flow),
+            }
+        }
+        result
+    }
+}
+
+/// The value a 'bắt' block receives for an error: the value thrown with
+/// 'ném', or an exception object for an evaluation error. Other diagnostics
+/// cannot be caught and are given back.
+fn caught_value(diag: Diag) -> Result<ValueObj, Diag> {
+    match diag.data {
+        DiagData::Thrown(value) => Ok(value),
+        DiagData::EvalError(error) => Ok(ValueObj::Exception(ExceptionObj::new(&error))),
+        _ => Err(diag),
+    }
+}
+
+impl Interpretable for stmt::NemStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        let value = self.value.evaluate(interpreter)?;
+        Err(Diag {
+            line: interpreter.line(),
+            data: DiagData::Thrown(value),
+        })
+    }
+}
+
+impl Interpretable for stmt::SoKhopStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        let subject = self.subject.evaluate(interpreter)?;
+        for case in &self.cases {
+            // The names a pattern binds are only visible to its guard and body.
+            let scope = Scope::child(interpreter.scope());
+            if !match_pattern(interpreter, &case.pattern, &subject, &scope)? {
+                continue;
+            }
+            if let Some(guard) = &case.guard {
+                let holds = interpreter
+                    .with_scope(scope.clone(), |interpreter| guard.evaluate(interpreter))?;
+                if !holds.is_truthy() {
+                    continue;
+                }
+            }
+            return interpret_nested(&case.body, interpreter, scope);
+        }
+        Err(Diag {
+            line: interpreter.line(),
+            data: DiagData::EvalError(EvalError::NonExhaustiveMatch {
+                value: subject.repr(),
             }),
+        })
+    }
+}
+
+/// Checks `value` against `pattern`, binding the names it captures in
+/// `scope`.
+fn match_pattern(
+    interpreter: &mut Evaluator,
+    pattern: &Pattern,
+    value: &ValueObj,
+    scope: &Scope,
+) -> Result<bool, Diag> {
+    match pattern {
+        Pattern::Wildcard => Ok(true),
+        Pattern::Binding(ident) => {
+            scope.bind(interpreter.snippet(&ident.0), value.clone());
+            Ok(true)
+        }
+        Pattern::Literal { negative, lit } => {
+            let mut expected = lit.evaluate(interpreter)?;
+            if *negative {
+                expected = Operation {
+                    kind: OperationKind::NegativePrefix,
+                    operands: (ValueObj::Undefined, expected),
+                }
+                .evaluate(interpreter)?;
+            }
+            Ok(*value == expected)
         }
+        Pattern::Tuple(patterns) => match value {
+            ValueObj::Tuple(t) => match_sequence(interpreter, patterns, &t.0, scope, |rest| {
+                ValueObj::Tuple(TupleObj(rest))
+            }),
+            _ => Ok(false),
+        },
+        Pattern::List(patterns) => match value {
+            ValueObj::List(l) => {
+                let elements = l.0.borrow().clone();
+                match_sequence(interpreter, patterns, &elements, scope, |rest| {
+                    ValueObj::List(ListObj::new(rest))
+                })
+            }
+            _ => Ok(false),
+        },
+        Pattern::Rest(_) => unreachable!("a rest element is only parsed inside a sequence"),
+    }
+}
+
+/// Matches the elements of a tuple or list against the patterns of a
+/// sequence pattern. A rest element takes whatever the patterns before and
+/// after it leave over, collected by `collect`.
+fn match_sequence(
+    interpreter: &mut Evaluator,
+    patterns: &[Pattern],
+    elements: &[ValueObj],
+    scope: &Scope,
+    collect: fn(Vec<ValueObj>) -> ValueObj,
+) -> Result<bool, Diag> {
+    let rest = patterns.iter().position(|p| matches!(p, Pattern::Rest(_)));
+    let (before, after) = match rest {
+        Some(i) => (&patterns[..i], &patterns[i + 1..]),
+        None => (patterns, &patterns[patterns.len()..]),
+    };
+    let fits = match rest {
+        Some(_) => elements.len() >= before.len() + after.len(),
+        None => elements.len() == patterns.len(),
+    };
+    if !fits {
+        return Ok(false);
+    }
+    let tail = elements.len() - after.len();
+    for (pattern, element) in before.iter().zip(elements) {
+        if !match_pattern(interpreter, pattern, element, scope)? {
+            return Ok(false);
+        }
+    }
+    for (pattern, element) in after.iter().zip(&elements[tail..]) {
+        if !match_pattern(interpreter, pattern, element, scope)? {
+            return Ok(false);
+        }
+    }
+    if let Some(i) = rest
+        && let Pattern::Rest(Some(ident)) = &patterns[i]
+    {
+        let middle = elements[before.len()..tail].to_vec();
+        scope.bind(interpreter.snippet(&ident.0), collect(middle));
+    }
+    Ok(true)
+}
+
+impl Interpretable for stmt::TraVeStatement {
+    fn interpret(&self, interpreter: &mut Evaluator) -> Result<Flow, Diag> {
+        Ok(Flow::Return(match &self.value {
+            Some(expr) => expr.evaluate(interpreter)?,
+            None => ValueObj::Undefined,
+        }))
     }
 }
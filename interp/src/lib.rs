@@ -1,29 +1,112 @@
-use parser::{Span, parser::Parser, syntax::Programme};
+use parser::{
+    Span,
+    parser::Parser,
+    syntax::{Programme, expr::terminal::Keyword},
+};
 
-use crate::{interp::Interpretable, scope::Scope};
+use crate::{
+    diag::{Diag, DiagData, EvalError, Warning},
+    interp::{Flow, Interpretable},
+    obj::{ClassObj, ClosureObj, InstanceObj, MethodFn, MethodObj, ValueObj},
+    scope::Scope,
+};
 
+pub mod bigint;
+pub mod builtin;
 pub mod diag;
 pub mod eval;
 pub mod interp;
 pub mod obj;
 pub mod scope;
 
+/// How many function calls may be nested before giving up.
+pub const MAX_CALL_DEPTH: usize = 1000;
+
+/// How many bits an integer raised to a power may take. Multiplication
+/// takes time quadratic in the size of the integers, so larger powers would
+/// hang the interpreter rather than fail.
+pub const MAX_INTEGER_BITS: u64 = 1 << 18;
+
+/// How many bytes a repeated string may take, so that a huge count fails
+/// rather than exhausting memory.
+pub const MAX_STRING_BYTES: usize = 1 << 28;
+
+/// The stack an evaluator needs to reach `MAX_CALL_DEPTH`. Each nested call
+/// recurses through the evaluation of its statements and expressions, which
+/// takes up to some tens of kilobytes in a debug build, far more in total
+/// than the 8 MiB a main thread usually has. Interpreting on a thread with
+/// this much leaves `RecursionLimit`, rather than a stack overflow, to end
+/// runaway recursion.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// What dividing a float by zero, or any number with `/`, gives. A floor
+/// division or modulo of two integers by zero is always an error, as
+/// integers have no infinity or NaN.
+#[derive(Clone, Copy, Default)]
+pub enum DivisionByZero {
+    /// An infinity, or NaN for zero divided by zero, as IEEE 754 specifies.
+    #[default]
+    Ieee754,
+    /// A `DivisionByZero` evaluation error.
+    Error,
+}
+
 pub struct Evaluator<'a> {
     parser: Parser<'a>,
-    global: Scope<'a>,
+    /// The scope of the block being interpreted.
+    scope: Scope,
+    /// How many function calls are being made.
+    call_depth: usize,
+    /// The line being executed, which runtime errors are reported at.
+    line: u32,
+    division_by_zero: DivisionByZero,
+    warn_shadowing: bool,
+    /// The warnings printed so far, with the lines they were given for.
+    warnings: Vec<(u32, Warning)>,
 }
 
 impl<'a> Evaluator<'a> {
     pub fn new(input: &'a str) -> Self {
+        let builtins = Scope::new();
+        builtin::register(&builtins);
         Evaluator {
             parser: Parser::new(input),
-            global: Scope::new(),
+            // The programme's top level, in front of the builtins.
+            scope: Scope::child(&builtins),
+            call_depth: 0,
+            line: 1,
+            division_by_zero: DivisionByZero::default(),
+            warn_shadowing: false,
+            warnings: Vec::new(),
         }
     }
 
+    pub fn division_by_zero(&self) -> DivisionByZero {
+        self.division_by_zero
+    }
+
+    pub fn set_division_by_zero(&mut self, policy: DivisionByZero) {
+        self.division_by_zero = policy;
+    }
+
+    /// Whether declaring a name that hides one from an outer scope, such as
+    /// a builtin or a variable of an enclosing block, prints a warning.
+    pub fn set_warn_shadowing(&mut self, warn: bool) {
+        self.warn_shadowing = warn;
+    }
+
+    pub fn warn(&mut self, line: u32, warning: Warning) {
+        eprintln!("Warning at line {}: {}", line, warning);
+        self.warnings.push((line, warning));
+    }
+
+    pub fn warnings(&self) -> &[(u32, Warning)] {
+        &self.warnings
+    }
+
     pub fn parse(&mut self) -> Option<Programme> {
         let prog = self.parser.visit_programme();
-        if self.parser.diag.len() > 0 {
+        if !self.parser.diag.is_empty() {
             self.parser.print_diags();
             None
         } else {
@@ -31,10 +114,6 @@ impl<'a> Evaluator<'a> {
         }
     }
 
-    pub fn cur_line(&self) -> u32 {
-        self.parser.cur_line
-    }
-
     pub fn interpret(&mut self, prog: &Option<Programme>) {
         match prog {
             Some(p) => {
@@ -57,4 +136,1065 @@ impl<'a> Evaluator<'a> {
     pub fn snippet(&self, span: &Span) -> &'a str {
         self.parser.get_snippet(span)
     }
+
+    /// Looks `name` up from the current block outwards.
+    pub fn lookup(&self, name: &str) -> Option<ValueObj> {
+        self.scope.get(name)
+    }
+
+    /// Overwrites `name` where `lookup` would find it, returning false when
+    /// it has never been declared.
+    pub fn assign(&mut self, name: &str, value: ValueObj) -> bool {
+        self.scope.set(name, value)
+    }
+
+    /// Declares `name` in the current block. Declaring it twice in the same
+    /// block is an error, while hiding a name from an outer block is
+    /// allowed and only warned about if asked to.
+    pub fn declare(&mut self, name: &str, value: ValueObj) -> Result<(), Diag> {
+        let line = self.line;
+        if !self.scope.declare(name, value) {
+            return Err(Diag {
+                line,
+                data: DiagData::EvalError(EvalError::AlreadyDeclaredInScope {
+                    name: name.to_string(),
+                }),
+            });
+        }
+        if self.warn_shadowing
+            && let Some(outer) = self.scope.parent()
+            && outer.get(name).is_some()
+        {
+            self.warn(
+                line,
+                Warning::Shadowing {
+                    name: name.to_string(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// The scope of the block being interpreted.
+    pub fn scope(&self) -> &Scope {
+        &self.scope
+    }
+
+    /// Runs `f` with `scope` as the current scope, then restores the
+    /// previous one.
+    pub fn with_scope<T>(&mut self, scope: Scope, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = std::mem::replace(&mut self.scope, scope);
+        let result = f(self);
+        self.scope = outer;
+        result
+    }
+
+    /// The line being executed.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Runs `f` with `line` as the line being executed, then restores the
+    /// previous one, so that an error raised once a statement's operands
+    /// or a call have been evaluated is reported at the enclosing line.
+    pub fn at_line<T>(&mut self, line: u32, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = std::mem::replace(&mut self.line, line);
+        let result = f(self);
+        self.line = outer;
+        result
+    }
+
+    /// Calls a method on the receiver it was looked up on. Arity errors of
+    /// builtin methods count only the arguments written between the
+    /// parentheses.
+    fn call_method(
+        &mut self,
+        method: MethodObj,
+        mut args: Vec<ValueObj>,
+    ) -> Result<ValueObj, Diag> {
+        let builtin = match method.func {
+            MethodFn::Builtin(builtin) => builtin,
+            MethodFn::Closure(closure) => {
+                return self.call_closure(closure, Some(*method.receiver), args);
+            }
+        };
+        args.insert(0, *method.receiver);
+        (builtin.func)(self, args).map_err(|mut diag| {
+            if let DiagData::EvalError(EvalError::ArityMismatch {
+                name,
+                expected,
+                found,
+            }) = &mut diag.data
+                && name == builtin.name
+            {
+                *expected -= 1;
+                *found -= 1;
+            }
+            diag
+        })
+    }
+
+    /// Makes an instance of a class and runs its constructor, if it has one,
+    /// on the instance.
+    fn instantiate(&mut self, class: ClassObj, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+        let instance = ValueObj::Instance(InstanceObj::new(class.clone()));
+        match class.method(ClassObj::CONSTRUCTOR) {
+            Some(constructor) => {
+                self.call_closure(constructor, Some(instance.clone()), args)?;
+            }
+            None if !args.is_empty() => {
+                return Err(Diag {
+                    line: self.line,
+                    data: DiagData::EvalError(EvalError::ArityMismatch {
+                        name: class.0.name.clone(),
+                        expected: 0,
+                        found: args.len(),
+                    }),
+                });
+            }
+            None => {}
+        }
+        Ok(instance)
+    }
+
+    pub fn call(&mut self, callee: ValueObj, args: Vec<ValueObj>) -> Result<ValueObj, Diag> {
+        match callee {
+            ValueObj::Function(func) => self.call_closure(func, None, args),
+            ValueObj::Builtin(builtin) => (builtin.func)(self, args),
+            ValueObj::Method(method) => self.call_method(method, args),
+            ValueObj::Class(class) => self.instantiate(class, args),
+            _ => Err(Diag {
+                line: self.line,
+                data: DiagData::EvalError(EvalError::NotCallable {
+                    operand: callee.describe(),
+                }),
+            }),
+        }
+    }
+
+    /// Runs the body of a function in a new frame in front of the scope it
+    /// was defined in. A method called without a receiver, as in
+    /// `Con vật.khởi tạo(bản thân, tên)`, takes it as its first argument.
+    fn call_closure(
+        &mut self,
+        func: ClosureObj,
+        receiver: Option<ValueObj>,
+        mut args: Vec<ValueObj>,
+    ) -> Result<ValueObj, Diag> {
+        let unbound = func.is_method && receiver.is_none();
+        let expected = func.def.params.len() + unbound as usize;
+        if expected != args.len() {
+            return Err(Diag {
+                line: self.line,
+                data: DiagData::EvalError(EvalError::ArityMismatch {
+                    name: func.name,
+                    expected,
+                    found: args.len(),
+                }),
+            });
+        }
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(Diag {
+                line: self.line,
+                data: DiagData::EvalError(EvalError::RecursionLimit {
+                    limit: MAX_CALL_DEPTH,
+                }),
+            });
+        }
+        let frame = Scope::child(&func.env);
+        let receiver = match unbound {
+            true => Some(args.remove(0)),
+            false => receiver,
+        };
+        if let Some(receiver) = receiver {
+            frame.bind(Keyword::BanThan.as_str(), receiver);
+        }
+        for (param, arg) in func.def.params.iter().zip(args) {
+            frame.bind(self.snippet(&param.0), arg);
+        }
+        self.call_depth += 1;
+        let flow = self.with_scope(frame, |eval| func.def.body.interpret(eval));
+        self.call_depth -= 1;
+        Ok(match flow? {
+            Flow::Return(value) => value,
+            _ => ValueObj::Undefined,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{panic, thread};
+
+    use parser::diag::{DiagData, Error};
+
+    use crate::{
+        DivisionByZero, Evaluator, STACK_SIZE,
+        diag::{DiagData as EvalDiagData, EvalError, Warning},
+        interp::Interpretable,
+    };
+
+    /// Interprets `input`, then checks how a top-level name displays. Like
+    /// `vi`, it interprets on a thread with the stack the evaluator needs.
+    fn assert_global(input: &str, name: &str, expected: &str) {
+        assert_global_with(input, name, expected, |_| {});
+    }
+
+    /// Like `assert_global`, with the evaluator set up by `setup` first.
+    fn assert_global_with(input: &str, name: &str, expected: &str, setup: fn(&mut Evaluator)) {
+        let found = thread::scope(|scope| {
+            thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn_scoped(scope, || {
+                    let mut eval = Evaluator::new(input);
+                    setup(&mut eval);
+                    let prog = eval.parse();
+                    assert!(prog.is_some(), "the programme does not parse");
+                    eval.interpret(&prog);
+                    eval.lookup(name).map(|value| value.to_string())
+                })
+                .expect("failed to spawn the interpreter thread")
+                .join()
+        })
+        .unwrap_or_else(|payload| panic::resume_unwind(payload));
+        assert_eq!(found.as_deref(), Some(expected), "value of '{}'", name);
+    }
+
+    #[test]
+    fn conditional_takes_first_true_branch() {
+        let input = "\
+hàm phân loại(n)
+    nếu n < 0 thì
+        trả về \"âm\"
+    còn nếu n == 0 thì
+        trả về \"không\"
+    còn nếu n < 10 thì
+        trả về \"nhỏ\"
+    không thì
+        trả về \"lớn\"
+cho kết quả = (phân loại(-1), phân loại(0), phân loại(5), phân loại(50))
+";
+        assert_global(input, "kết quả", "(\"âm\", \"không\", \"nhỏ\", \"lớn\")");
+    }
+
+    #[test]
+    fn conditional_without_else_may_run_nothing() {
+        let input = "\
+cho x = 1
+nếu x > 1 thì
+    x = 2
+còn nếu x > 5 thì
+    x = 3
+";
+        assert_global(input, "x", "1");
+    }
+
+    #[test]
+    fn while_loop_breaks_and_continues() {
+        let input = "\
+cho i = 0
+cho tổng = 0
+trong khi đúng thì
+    i += 1
+    nếu i > 10 thì
+        dừng
+    nếu i % 2 == 0 thì
+        tiếp tục
+    tổng += i
+cho kết quả = (i, tổng)
+";
+        assert_global(input, "kết quả", "(11, 25)");
+    }
+
+    #[test]
+    fn line_breaks_end_expressions_outside_brackets() {
+        let input = "\
+cho x = 0
+cho xs = [1,
+    2]
+trong khi x < 3 thì
+    x += 1
+hàm f()
+    trả về
+    x = 100
+f()
+cho y = (1
+    + 2)
+cho kết quả = (x, xs, y)
+";
+        assert_global(input, "kết quả", "(3, [1, 2], 3)");
+        for input in [
+            "cho x = 1 +\n2\n",
+            "cho x = đúng và\nsai\n",
+            "cho x = -\n1\n",
+        ] {
+            let mut eval = Evaluator::new(input);
+            assert!(eval.parse().is_none(), "{:?} parses", input);
+        }
+    }
+
+    #[test]
+    fn break_leaves_only_the_innermost_loop() {
+        let input = "\
+cho lần = 0
+cho i = 0
+trong khi i < 3 thì
+    i += 1
+    trong khi đúng thì
+        lần += 1
+        dừng
+cho kết quả = (i, lần)
+";
+        assert_global(input, "kết quả", "(3, 3)");
+    }
+
+    #[test]
+    fn for_each_over_strings_tuples_and_lists() {
+        let input = "\
+cho chữ = \"\"
+với mỗi c trong \"abc\" thì
+    chữ = c + chữ
+cho tổng = 0
+với mỗi n trong (1, 2, 3) thì
+    tổng += n
+với mỗi n trong [10, 20] thì
+    tổng += n
+cho kết quả = (chữ, tổng)
+";
+        assert_global(input, "kết quả", "(\"cba\", 36)");
+    }
+
+    #[test]
+    fn for_each_over_ranges() {
+        let input = "\
+cho các số = []
+với mỗi i trong khoảng(3) thì
+    các số.thêm(i)
+với mỗi i trong khoảng(10, 0, -4) thì
+    các số.thêm(i)
+cho kết quả = (các số, khoảng(1, 4), 7 trong khoảng(1, 10, 3), 8 trong khoảng(1, 10, 3))
+";
+        assert_global(
+            input,
+            "kết quả",
+            "([0, 1, 2, 10, 6, 2], khoảng(1, 4), đúng, sai)",
+        );
+    }
+
+    #[test]
+    fn range_with_zero_step_is_an_error() {
+        let input = "\
+cho kết quả = ()
+thử
+    khoảng(1, 5, 0)
+bắt lỗi
+    kết quả = lỗi.loại
+";
+        assert_global(input, "kết quả", "zero step");
+    }
+
+    #[test]
+    fn block_scoped_names_end_with_the_block() {
+        let input = "\
+cho kết quả = []
+cho x = 1
+nếu đúng thì
+    cho y = x + 1
+    nếu đúng thì
+        kết quả.thêm(x + y)
+    cho x = 10
+    kết quả.thêm(x)
+kết quả.thêm(x)
+thử
+    kết quả.thêm(y)
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+";
+        assert_global(input, "kết quả", "[3, 10, 1, \"undefined name\"]");
+    }
+
+    #[test]
+    fn redeclaring_in_the_same_block_is_an_error_at_its_line() {
+        let input = "\
+cho x = 1
+cho y = 2
+cho x = 3
+cho z = 4
+";
+        let mut eval = Evaluator::new(input);
+        let prog = eval.parse().expect("the programme does not parse");
+        let diag = prog
+            .statements
+            .iter()
+            .find_map(|stmt| stmt.interpret(&mut eval).err())
+            .expect("redeclaring 'x' is not an error");
+        assert_eq!(diag.line, 3);
+        assert!(matches!(
+            diag.data,
+            EvalDiagData::EvalError(EvalError::AlreadyDeclaredInScope { .. })
+        ));
+        assert!(eval.lookup("z").is_none());
+    }
+
+    #[test]
+    fn runtime_errors_are_reported_at_the_line_executed() {
+        let error_line = |input: &str| {
+            let mut eval = Evaluator::new(input);
+            let prog = eval.parse().expect("the programme does not parse");
+            prog.statements
+                .iter()
+                .find_map(|stmt| stmt.interpret(&mut eval).err())
+                .expect("the programme runs without error")
+                .line
+        };
+        assert_eq!(error_line("cho xs = [1]\nin(xs[5])\ncho y = 1\n"), 2);
+        let function = "hàm f(a)\n    trả về a // 0\ncho y = 1\n";
+        assert_eq!(error_line(&format!("{function}cho x = f(1)\n")), 2);
+        assert_eq!(error_line(&format!("{function}cho x = f(1, 2)\n")), 4);
+        let function = "hàm f(a)\n    trả về a\n";
+        assert_eq!(error_line(&format!("{function}cho x = f(1) + \"a\"\n")), 3);
+        assert_eq!(error_line("cho x = (1 +\n2 +\n\"a\")\n"), 2);
+        assert_eq!(error_line("cho y = 1\nz = [\n1,\n]\n"), 2);
+    }
+
+    #[test]
+    fn shadowing_warns_at_the_declaring_line_only_if_asked() {
+        let input = "\
+cho x = 1
+hàm f(n)
+    cho x = n
+    trả về x
+cho in = 2
+cho y = f(3)
+";
+        for warn in [false, true] {
+            let mut eval = Evaluator::new(input);
+            eval.set_warn_shadowing(warn);
+            let prog = eval.parse();
+            eval.interpret(&prog);
+            let warnings: Vec<_> = eval
+                .warnings()
+                .iter()
+                .map(|(line, Warning::Shadowing { name })| (*line, name.as_str()))
+                .collect();
+            let expected = if warn {
+                vec![(5, "in"), (3, "x")]
+            } else {
+                vec![]
+            };
+            assert_eq!(warnings, expected);
+            assert_eq!(
+                eval.lookup("y").map(|value| value.to_string()).as_deref(),
+                Some("3")
+            );
+        }
+    }
+
+    #[test]
+    fn function_returns_a_value_or_undefined() {
+        let input = "\
+hàm bình phương(n)
+    trả về n * n
+hàm bỏ qua()
+    cho x = 1
+hàm tìm chẵn(các số)
+    với mỗi n trong các số thì
+        nếu n % 2 == 0 thì
+            trả về n
+    trả về -1
+cho kết quả = (bình phương(7), bỏ qua(), tìm chẵn([1, 3, 4, 6]), tìm chẵn([1]))
+";
+        assert_global(input, "kết quả", "(49, undefined, 4, -1)");
+    }
+
+    #[test]
+    fn calling_with_wrong_arity_is_an_error() {
+        let input = "\
+hàm cộng(a, b)
+    trả về a + b
+cho kết quả = ()
+thử
+    cộng(1)
+bắt lỗi
+    kết quả = (lỗi.loại, lỗi.thông điệp)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "(\"argument count\", \"Function 'cộng' takes 2 argument(s) but 1 were given\")",
+        );
+    }
+
+    #[test]
+    fn recursion_stops_at_the_limit() {
+        let input = "\
+hàm đệ quy(n)
+    nếu n == 0 thì
+        trả về 0
+    trả về 1 + đệ quy(n - 1)
+cho sâu = đệ quy(999)
+cho kết quả = ()
+thử
+    đệ quy(1000)
+bắt lỗi
+    kết quả = lỗi.loại
+";
+        assert_global(input, "sâu", "999");
+        assert_global(input, "kết quả", "recursion limit");
+    }
+
+    #[test]
+    fn calls_chain_on_returned_functions() {
+        let input = "\
+hàm cộng(a)
+    hàm cộng tiếp(b)
+        hàm thêm(c)
+            trả về a + b + c
+        trả về thêm
+    trả về cộng tiếp
+cho kết quả = cộng(1)(20)(300)
+";
+        assert_global(input, "kết quả", "321");
+    }
+
+    #[test]
+    fn comparisons_chain_and_evaluate_operands_once() {
+        let input = "\
+cho lần = 0
+hàm giữa()
+    lần += 1
+    trả về 5
+cho kết quả = (1 < giữa() <= 5, 1 < giữa() < 5, 3 > 2 == đúng, lần)
+";
+        assert_global(input, "kết quả", "(đúng, sai, sai, 2)");
+    }
+
+    #[test]
+    fn and_or_short_circuit_and_give_an_operand() {
+        let input = "\
+cho nhật ký = []
+hàm ghi(v)
+    nhật ký.thêm(v)
+    trả về v
+cho a = ghi(0) và ghi(1)
+cho b = ghi(2) hoặc ghi(3)
+cho c = ghi(\"\") hoặc ghi(\"x\")
+cho kết quả = (a, b, c, không a, không \"x\", nhật ký)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "(0, 2, \"x\", đúng, sai, [0, 2, \"\", \"x\"])",
+        );
+    }
+
+    #[test]
+    fn strings_concatenate_and_repeat() {
+        let input = "\
+cho tên = \"Việt\"
+cho kết quả = (\"Xin chào \" + tên, \"ab\" * 3, 2 * \"ha\", \"x\" * -1, \"y\" * 2.0)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "(\"Xin chào Việt\", \"ababab\", \"haha\", \"\", \"yy\")",
+        );
+    }
+
+    #[test]
+    fn huge_string_repetition_is_an_error() {
+        let input = "\
+cho kết quả = []
+thử
+    cho x = \"ab\" * 1e30
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    cho x = 1e300 * \"\"
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    cho x = \"ab\" * 100000000000000000
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    cho x = 10 ** 30 * \"ab\"
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "[\"string too long\", \"string too long\", \"string too long\"]",
+        );
+    }
+
+    #[test]
+    fn string_escapes_are_processed() {
+        let input = "\
+cho kết quả = (độ dài(\"a\\tb\\nc\"), \"\\\"\" + '\\'', \"\\u{1F600}\" == \"😀\", \"\\\\\")
+";
+        assert_global(input, "kết quả", "(5, \"\\\"'\", đúng, \"\\\\\")");
+    }
+
+    #[test]
+    fn dedented_strings_keep_escapes_and_interpolations() {
+        let input = "\
+cho tên = \"An\"
+cho a = d\"\"\"
+    \\tx
+    \\ty
+    \"\"\"
+cho b = fd\"\"\"
+    Xin chào {tên}\u{FFFC}
+      {tên}!\u{FFFC}
+    \"\"\"
+cho c = fd\"\"\"
+  {tên}
+    z\"\"\"
+cho kết quả = (a == \"\\tx\\n\\ty\\n\", b == \"Xin chào An\u{FFFC}\\n  An!\u{FFFC}\\n\", c)
+";
+        assert_global(input, "kết quả", "(đúng, đúng, \"An\\n  z\")");
+    }
+
+    #[test]
+    fn integers_stay_exact_and_floats_show_a_fraction() {
+        let input = "\
+cho kết quả = (2 ** 100, -7 // 2, -7 % 2, 7 // -2, 4 / 2, 1e3, 2 ** -1, 0.1 + 0.2)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "(1267650600228229401496703205376, -4, 1, -4, 2.0, 1000.0, 0.5, 0.30000000000000004)",
+        );
+    }
+
+    #[test]
+    fn integer_floor_division_by_zero_is_always_an_error() {
+        let input = "\
+cho kết quả = []
+thử
+    cho x = 1 // 0
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    cho x = 1 % 0
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+kết quả.thêm(1 / 0)
+kết quả.thêm(1.0 // 0)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "[\"division by zero\", \"division by zero\", +inf, +inf]",
+        );
+        assert_global_with(
+            input,
+            "kết quả",
+            "[\"division by zero\", \"division by zero\"]",
+            |eval| eval.set_division_by_zero(DivisionByZero::Error),
+        );
+    }
+
+    #[test]
+    fn huge_integer_power_is_an_error() {
+        let input = "\
+cho kết quả = ()
+thử
+    kết quả = 2 ** 10000000
+bắt lỗi
+    kết quả = lỗi.loại
+";
+        assert_global(input, "kết quả", "integer too large");
+    }
+
+    #[test]
+    fn letters_after_numbers_are_malformed_literals() {
+        for input in ["in(1e)", "in(123abc)", "cho x = 1.5x"] {
+            let mut eval = Evaluator::new(input);
+            assert!(eval.parse().is_none(), "'{}' parses", input);
+            assert!(
+                matches!(
+                    eval.parser.diag.first().map(|diag| &diag.data),
+                    Some(DiagData::Err(Error::MalformedLiteral { .. }))
+                ),
+                "'{}' is not reported as a malformed literal",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn division_by_zero_follows_the_policy() {
+        let input = "\
+cho kết quả = ()
+thử
+    kết quả = (1 / 0, -1.5 / 0, 0 / 0.0, 2.0 % 0, 0 ** -1)
+bắt lỗi
+    kết quả = lỗi.loại
+";
+        assert_global(input, "kết quả", "(+inf, -inf, NaN, NaN, +inf)");
+        assert_global_with(input, "kết quả", "division by zero", |eval| {
+            eval.set_division_by_zero(DivisionByZero::Error)
+        });
+    }
+
+    #[test]
+    fn list_builtins_change_the_list_in_place() {
+        let input = "\
+cho a = [3, 1]
+cho b = a
+thêm(a, 2)
+a.thêm(5)
+chèn(a, 0, 0)
+a.chèn(-1, 4)
+a.chèn(100, 9)
+xóa(a, 1)
+b.sắp xếp()
+cho kết quả = (b, 9 trong a, 1 trong a, độ dài(a))
+";
+        assert_global(input, "kết quả", "([0, 2, 3, 4, 5, 9], đúng, sai, 6)");
+    }
+
+    #[test]
+    fn list_errors() {
+        let input = "\
+cho kết quả = []
+thử
+    [1, 2].xóa(3)
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    [1, \"a\"].sắp xếp()
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    [1.5, 0 / 0, 2].sắp xếp()
+bắt lỗi
+    kết quả.thêm(lỗi.thông điệp)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "[\"not in list\", \"unsupported operation\", \
+             \"Cannot sort NaN, which is not ordered against any number\"]",
+        );
+    }
+
+    #[test]
+    fn lists_compare_by_their_elements() {
+        let input = "\
+cho a = [1, [2, 3]]
+cho kết quả = (a == [1, [2, 3]], a != [1, [2]], [1, 2] < [1, 3], [2] > [1, 5], [] == ())
+";
+        assert_global(input, "kết quả", "(đúng, đúng, đúng, đúng, sai)");
+    }
+
+    #[test]
+    fn lists_containing_themselves() {
+        let input = "\
+cho a = [[1]]
+a.thêm(a)
+cho lỗi sắp xếp = ()
+thử
+    a.sắp xếp()
+bắt lỗi
+    lỗi sắp xếp = lỗi.loại
+cho b = []
+b.thêm(b)
+cho c = []
+c.thêm(c)
+cho kết quả = (a, lỗi sắp xếp, b == c, b < c, b trong b)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "([[1], [...]], \"unsupported operation\", đúng, sai, đúng)",
+        );
+    }
+
+    #[test]
+    fn dictionaries_keep_insertion_order() {
+        let input = "\
+cho d = {\"b\": 1, \"a\": 2, 1: \"một\", (1, 2): đúng}
+d[\"b\"] = 10
+d[\"c\"] = 3
+cho kết quả = (d[\"a\"], d[1.0], d[(1, 2)], d.lấy(\"z\", 0), \"c\" trong d)
+";
+        assert_global(
+            input,
+            "d",
+            "{\"b\": 10, \"a\": 2, 1: \"một\", (1, 2): đúng, \"c\": 3}",
+        );
+        assert_global(input, "kết quả", "(2, \"một\", đúng, 0, đúng)");
+    }
+
+    #[test]
+    fn dictionary_errors() {
+        let input = "\
+cho d = {1: 2}
+cho kết quả = []
+thử
+    cho x = d[3]
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    d[[1]] = 2
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    cho e = {{}: 1}
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "[\"missing key\", \"unhashable\", \"unhashable\"]",
+        );
+    }
+
+    #[test]
+    fn dictionaries_containing_themselves() {
+        let input = "\
+cho d = {}
+d[\"x\"] = d
+cho e = {}
+e[\"x\"] = e
+cho kết quả = (d, d == e)
+";
+        assert_global(input, "kết quả", "({\"x\": {...}}, đúng)");
+    }
+
+    #[test]
+    fn indices_and_slices() {
+        let input = "\
+cho a = [0, 1, 2, 3, 4, 5]
+cho s = \"xin chào\"
+cho t = (1, 2, 3)
+cho của danh sách = (a[-1], a[1:3], a[::2], a[::-1], a[-2:], a[:100])
+cho của chuỗi = (s[4:], s[-1], s[::-1][:3])
+cho của bộ = (t[1:], t[5:9], t[-3])
+";
+        assert_global(
+            input,
+            "của danh sách",
+            "(5, [1, 2], [0, 2, 4], [5, 4, 3, 2, 1, 0], [4, 5], [0, 1, 2, 3, 4, 5])",
+        );
+        assert_global(input, "của chuỗi", "(\"chào\", \"o\", \"oàh\")");
+        assert_global(input, "của bộ", "((2, 3), (), 1)");
+    }
+
+    #[test]
+    fn index_assignment_changes_the_list() {
+        let input = "\
+cho a = [1, 2, 3]
+cho b = a
+a[0] = 10
+a[-1] += 5
+cho kết quả = []
+thử
+    a[3] = 0
+bắt lỗi
+    kết quả.thêm(lỗi.thông điệp)
+thử
+    cho x = a[::0]
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+thử
+    cho t = (1, 2)
+    t[0] = 3
+bắt lỗi
+    kết quả.thêm(lỗi.loại)
+kết quả.thêm(b)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "[\"Index 3 is out of range for a length of 3\", \"zero step\", \
+             \"not assignable\", [10, 2, 8]]",
+        );
+    }
+
+    #[test]
+    fn closure_mutates_captured_variable() {
+        let input = "\
+cho tổng = 0
+hàm cộng(n)
+    tổng += n
+cộng(2)
+cộng(3)
+";
+        assert_global(input, "tổng", "5");
+    }
+
+    #[test]
+    fn closure_sees_later_changes() {
+        let input = "\
+cho x = 1
+hàm đọc()
+    trả về x
+x = 2
+cho y = đọc()
+";
+        assert_global(input, "y", "2");
+    }
+
+    #[test]
+    fn escaping_closures_keep_separate_state() {
+        let input = "\
+hàm tạo bộ đếm()
+    cho đếm = 0
+    hàm tăng()
+        đếm += 1
+        trả về đếm
+    trả về tăng
+cho a = tạo bộ đếm()
+cho b = tạo bộ đếm()
+a()
+a()
+cho kết quả = (a(), b())
+";
+        assert_global(input, "kết quả", "(3, 1)");
+    }
+
+    #[test]
+    fn loop_iterations_are_captured_separately() {
+        let input = "\
+cho bộ đọc = []
+với mỗi i trong [1, 2, 3] thì
+    hàm đọc()
+        trả về i
+    bộ đọc.thêm(đọc)
+cho kết quả = [bộ đọc[0](), bộ đọc[2]()]
+";
+        assert_global(input, "kết quả", "[1, 3]");
+    }
+
+    #[test]
+    fn nested_function_calls_itself() {
+        let input = "\
+hàm ngoài(n)
+    hàm giai thừa(k)
+        nếu k <= 1 thì
+            trả về 1
+        trả về k * giai thừa(k - 1)
+    trả về giai thừa(n)
+cho kết quả = ngoài(5)
+";
+        assert_global(input, "kết quả", "120");
+    }
+
+    #[test]
+    fn constructor_sets_fields_and_methods_read_them() {
+        let input = "\
+lớp Điểm
+    hàm khởi tạo(x, y)
+        bản thân.x = x
+        bản thân.y = y
+    hàm dời(dx)
+        bản thân.x += dx
+cho p = Điểm(1, 2)
+p.dời(3)
+cho kết quả = (p.x, p.y, p)
+";
+        assert_global(input, "kết quả", "(4, 2, <đối tượng Điểm>)");
+    }
+
+    #[test]
+    fn subclass_inherits_and_overrides_methods() {
+        let input = "\
+lớp Con vật
+    hàm khởi tạo(tên)
+        bản thân.tên = tên
+    hàm tiếng()
+        trả về \"...\"
+    hàm kêu()
+        trả về f\"{bản thân.tên}: {bản thân.tiếng()}\"
+lớp Chó kế thừa Con vật
+    hàm tiếng()
+        trả về \"gâu\"
+cho kết quả = [Con vật(\"Mèo\").kêu(), Chó(\"Lu\").kêu()]
+";
+        assert_global(input, "kết quả", "[\"Mèo: ...\", \"Lu: gâu\"]");
+    }
+
+    #[test]
+    fn evaluation_error_is_caught_with_its_kind() {
+        let input = "\
+cho kết quả = ()
+thử
+    cho x = [1, 2][5]
+bắt lỗi
+    kết quả = lỗi
+cho loại = []
+thử
+    in(y)
+bắt lỗi
+    loại.thêm(lỗi.loại)
+thử
+    in y(1)
+bắt lỗi
+    loại.thêm(lỗi.loại)
+";
+        assert_global(
+            input,
+            "kết quả",
+            "<lỗi index out of range: Index 5 is out of range for a length of 2>",
+        );
+        assert_global(input, "loại", "[\"undefined name\", \"undefined name\"]");
+    }
+
+    #[test]
+    fn thrown_value_is_caught_and_finally_runs() {
+        let input = "\
+cho nhật ký = []
+hàm kiểm tra(n)
+    thử
+        nếu n < 0 thì
+            ném (\"âm\", n)
+        nhật ký.thêm(n)
+    cuối cùng
+        nhật ký.thêm(\"xong\")
+thử
+    kiểm tra(1)
+    kiểm tra(-2)
+bắt giá trị lỗi
+    nhật ký.thêm(giá trị lỗi)
+";
+        assert_global(input, "nhật ký", "[1, \"xong\", \"xong\", (\"âm\", -2)]");
+    }
+
+    #[test]
+    fn match_destructures_with_rest_and_guards() {
+        let input = "\
+hàm phân loại(v)
+    so khớp v thì
+        trường hợp (0, _) thì
+            trả về \"trục\"
+        trường hợp (x, y) nếu x == y thì
+            trả về x
+        trường hợp [đầu, *còn lại] thì
+            trả về còn lại
+        trường hợp _ thì
+            trả về \"khác\"
+cho kết quả = [phân loại((0, 1)), phân loại((2, 2)), phân loại([1, 2, 3]), phân loại(-1)]
+";
+        assert_global(input, "kết quả", "[\"trục\", 2, [2, 3], \"khác\"]");
+    }
+
+    #[test]
+    fn non_exhaustive_match_is_an_error() {
+        let input = "\
+cho kết quả = ()
+thử
+    so khớp 3 thì
+        trường hợp 1 thì
+            kết quả = 1
+bắt lỗi
+    kết quả = lỗi.loại
+";
+        assert_global(input, "kết quả", "no match");
+    }
 }
@@ -1,26 +1,58 @@
-use std::fmt::Display;
+use std::{cell::RefCell, cmp::Ordering, collections::HashMap, fmt::Display, rc::Rc};
+
+use parser::syntax::stmt;
 
 use crate::{
-    Evaluator,
+    DivisionByZero, Evaluator, MAX_INTEGER_BITS, MAX_STRING_BYTES,
+    bigint::BigInt,
     diag::{Diag, DiagData, EvalError},
     eval::Evaluable,
+    scope::Scope,
 };
 
+/// An integer of any size, which arithmetic keeps exact.
+#[derive(Clone)]
+pub struct IntegerObj(pub BigInt);
+
+impl Display for IntegerObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A floating-point number, which an integer becomes when mixed with one.
+/// It follows IEEE 754, so it may also be an infinity or NaN.
 #[derive(Clone)]
 pub struct NumericalObj(pub f64);
 
+/// A whole float keeps its fractional part, e.g. `2.0`, so that it can be
+/// told apart from an integer.
 impl Display for NumericalObj {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        if self.0 == f64::INFINITY {
+            write!(f, "+inf")
+        } else {
+            write!(f, "{:?}", self.0)
+        }
     }
 }
 
 #[derive(Clone)]
-pub struct StringObj(pub &'static str);
+pub struct BooleanObj(pub bool);
+
+impl Display for BooleanObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", if self.0 { "đúng" } else { "sai" })
+    }
+}
+
+/// An immutable string, shared between every value that holds it.
+#[derive(Clone)]
+pub struct StringObj(pub Rc<str>);
 
 impl Display for StringObj {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "\"{}\"", self.0)
+        write!(f, "{}", self.0)
     }
 }
 
@@ -29,18 +61,404 @@ pub struct TupleObj(pub Vec<ValueObj>);
 
 impl Display for TupleObj {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let elements: Vec<String> = self.0.iter().map(|v| format!("{}", v)).collect();
+        let elements: Vec<String> = self.0.iter().map(ValueObj::repr).collect();
         write!(f, "({})", elements.join(", "))
     }
 }
 
+/// The integers from `start` up to but excluding `stop`, every `step` of
+/// them, as made by 'khoảng'. They are produced one at a time as the range
+/// is iterated, so a large range takes no room.
+#[derive(Clone)]
+pub struct RangeObj {
+    pub start: BigInt,
+    pub stop: BigInt,
+    /// Never zero.
+    pub step: BigInt,
+}
+
+impl RangeObj {
+    /// Whether `i` comes before `stop` when counting by `step`.
+    fn before_stop(&self, i: &BigInt) -> bool {
+        match self.step.is_negative() {
+            true => *i > self.stop,
+            false => *i < self.stop,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.before_stop(&self.start)
+    }
+
+    /// Whether `int` is one of the integers of the range.
+    pub fn contains(&self, int: &BigInt) -> bool {
+        let reached = match self.step.is_negative() {
+            true => *int <= self.start,
+            false => *int >= self.start,
+        };
+        reached
+            && self.before_stop(int)
+            && (int - &self.start)
+                .div_rem_floor(&self.step)
+                .is_some_and(|(_, remainder)| remainder.is_zero())
+    }
+
+    pub fn iter(&self) -> ValueIter {
+        let range = self.clone();
+        let step = self.step.clone();
+        Box::new(
+            std::iter::successors(Some(self.start.clone()), move |i| Some(i + &step))
+                .take_while(move |i| range.before_stop(i))
+                .map(|i| ValueObj::Integer(IntegerObj(i))),
+        )
+    }
+}
+
+impl Display for RangeObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.step == BigInt::from(1u64) {
+            true => write!(f, "khoảng({}, {})", self.start, self.stop),
+            false => write!(f, "khoảng({}, {}, {})", self.start, self.stop, self.step),
+        }
+    }
+}
+
+/// A mutable list, shared by every value that refers to it, so a change
+/// made through one of them is seen through all.
+#[derive(Clone)]
+pub struct ListObj(pub Rc<RefCell<Vec<ValueObj>>>);
+
+impl ListObj {
+    pub fn new(elements: Vec<ValueObj>) -> Self {
+        ListObj(Rc::new(RefCell::new(elements)))
+    }
+}
+
+thread_local! {
+    /// Lists and dictionaries being displayed, so that one containing itself
+    /// shows `[...]` or `{...}` rather than recursing forever.
+    static DISPLAYED: RefCell<Vec<*const ()>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Formats the elements of a list or dictionary with `format`, unless it is
+/// already being displayed further up.
+fn display_once(ptr: *const (), format: impl FnOnce() -> Vec<String>) -> Option<String> {
+    if DISPLAYED.with_borrow(|displayed| displayed.contains(&ptr)) {
+        return None;
+    }
+    DISPLAYED.with_borrow_mut(|displayed| displayed.push(ptr));
+    let elements = format();
+    DISPLAYED.with_borrow_mut(|displayed| displayed.pop());
+    Some(elements.join(", "))
+}
+
+impl Display for ListObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let elements = display_once(Rc::as_ptr(&self.0).cast(), || {
+            self.0.borrow().iter().map(ValueObj::repr).collect()
+        });
+        write!(f, "[{}]", elements.as_deref().unwrap_or("..."))
+    }
+}
+
+/// The part of a value that a dictionary hashes its keys by. Values that
+/// compare equal, such as `1` and `1.0`, have equal keys.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum HashKey {
+    Integer(BigInt),
+    Float(u64),
+    Boolean(bool),
+    String(Rc<str>),
+    Tuple(Vec<HashKey>),
+}
+
+/// The entries of a dictionary in insertion order, indexed by key.
+#[derive(Default)]
+pub struct Dict {
+    entries: Vec<(ValueObj, ValueObj)>,
+    indices: HashMap<HashKey, usize>,
+}
+
+impl Dict {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &[(ValueObj, ValueObj)] {
+        &self.entries
+    }
+
+    pub fn get(&self, key: &HashKey) -> Option<&ValueObj> {
+        self.indices.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    /// Sets the value of a key, which keeps its place if it is already in
+    /// the dictionary.
+    pub fn insert(&mut self, hash_key: HashKey, key: ValueObj, value: ValueObj) {
+        match self.indices.get(&hash_key) {
+            Some(&i) => self.entries[i].1 = value,
+            None => {
+                self.indices.insert(hash_key, self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &HashKey) -> Option<ValueObj> {
+        let i = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(i);
+        for index in self.indices.values_mut() {
+            if *index > i {
+                *index -= 1;
+            }
+        }
+        Some(value)
+    }
+}
+
+/// A mutable dictionary, shared by every value that refers to it like a
+/// list.
+#[derive(Clone)]
+pub struct DictObj(pub Rc<RefCell<Dict>>);
+
+impl DictObj {
+    pub fn new() -> Self {
+        DictObj(Rc::new(RefCell::new(Dict::default())))
+    }
+
+    /// Sets the value of `key`, which must be hashable.
+    pub fn insert(&self, eval: &Evaluator, key: ValueObj, value: ValueObj) -> Result<(), Diag> {
+        let hash_key = key.hash_key(eval)?;
+        self.0.borrow_mut().insert(hash_key, key, value);
+        Ok(())
+    }
+
+    /// Looks the value of `key` up, failing if it is missing.
+    pub fn get(&self, eval: &Evaluator, key: &ValueObj) -> Result<ValueObj, Diag> {
+        match self.0.borrow().get(&key.hash_key(eval)?) {
+            Some(value) => Ok(value.clone()),
+            None => Err(Diag {
+                line: eval.line(),
+                data: DiagData::EvalError(EvalError::KeyNotFound { key: key.repr() }),
+            }),
+        }
+    }
+
+    /// Removes `key` and its value, failing if it is missing.
+    pub fn remove(&self, eval: &Evaluator, key: &ValueObj) -> Result<ValueObj, Diag> {
+        let removed = self.0.borrow_mut().remove(&key.hash_key(eval)?);
+        removed.ok_or_else(|| Diag {
+            line: eval.line(),
+            data: DiagData::EvalError(EvalError::KeyNotFound { key: key.repr() }),
+        })
+    }
+}
+
+impl Default for DictObj {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for DictObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries = display_once(Rc::as_ptr(&self.0).cast(), || {
+            self.0
+                .borrow()
+                .entries()
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key.repr(), value.repr()))
+                .collect()
+        });
+        write!(f, "{{{}}}", entries.as_deref().unwrap_or("..."))
+    }
+}
+
+impl IntegerObj {
+    /// Resolves an index into a sequence of `len` elements, where a negative
+    /// index counts from the end.
+    pub(crate) fn resolve_index(&self, eval: &Evaluator, len: usize) -> Result<usize, Diag> {
+        let resolved = match self.0.is_negative() {
+            true => &self.0 + &BigInt::from(len as u64),
+            false => self.0.clone(),
+        };
+        match resolved.to_usize() {
+            Some(i) if i < len => Ok(i),
+            _ => Err(Diag {
+                line: eval.line(),
+                data: DiagData::EvalError(EvalError::IndexOutOfRange {
+                    index: self.0.to_string(),
+                    length: len,
+                }),
+            }),
+        }
+    }
+}
+
+/// A function defined in the programme with 'hàm', together with the scope
+/// it was defined in. Its body sees the names of that scope as they are
+/// when it runs, and can change them, even after the block that declared
+/// them has ended.
+#[derive(Clone)]
+pub struct ClosureObj {
+    pub name: String,
+    pub def: Rc<stmt::HamStatement>,
+    pub env: Scope,
+    /// Whether it is a method of a class, which is called with the instance
+    /// as `bản thân`.
+    pub is_method: bool,
+}
+
+impl Display for ClosureObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<hàm {}>", self.name)
+    }
+}
+
+pub type BuiltinFn = fn(&mut Evaluator, Vec<ValueObj>) -> Result<ValueObj, Diag>;
+
+/// A function provided by the interpreter itself.
+#[derive(Clone)]
+pub struct BuiltinObj {
+    pub name: &'static str,
+    pub func: BuiltinFn,
+}
+
+impl Display for BuiltinObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<hàm dựng sẵn {}>", self.name)
+    }
+}
+
+/// A method looked up on a value, e.g. `danh_sách.thêm` or `chó.sủa`, which
+/// remembers that value to call the method on.
+#[derive(Clone)]
+pub struct MethodObj {
+    pub receiver: Box<ValueObj>,
+    pub func: MethodFn,
+}
+
+#[derive(Clone)]
+pub enum MethodFn {
+    /// A builtin method, which takes the receiver as its first argument.
+    Builtin(BuiltinObj),
+    /// A method of a class, which takes the receiver as `bản thân`.
+    Closure(ClosureObj),
+}
+
+impl MethodFn {
+    pub fn name(&self) -> &str {
+        match self {
+            MethodFn::Builtin(builtin) => builtin.name,
+            MethodFn::Closure(closure) => &closure.name,
+        }
+    }
+}
+
+impl Display for MethodObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<phương thức {}>", self.func.name())
+    }
+}
+
+/// An evaluation error caught by 'bắt', with the kind of error and its
+/// message as members `loại` and `thông điệp`.
+#[derive(Clone)]
+pub struct ExceptionObj {
+    pub kind: Rc<str>,
+    pub message: Rc<str>,
+}
+
+impl ExceptionObj {
+    pub fn new(error: &EvalError) -> Self {
+        ExceptionObj {
+            kind: error.kind().into(),
+            message: error.to_string().into(),
+        }
+    }
+}
+
+impl Display for ExceptionObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<lỗi {}: {}>", self.kind, self.message)
+    }
+}
+
+/// A class defined with 'lớp'.
+#[derive(Clone)]
+pub struct ClassObj(pub Rc<Class>);
+
+pub struct Class {
+    pub name: String,
+    pub parent: Option<ClassObj>,
+    pub methods: HashMap<Rc<str>, ClosureObj>,
+}
+
+impl ClassObj {
+    /// The name of the method called when the class is instantiated.
+    pub const CONSTRUCTOR: &str = "khởi tạo";
+
+    /// Looks a method up in the class, then in the classes it inherits.
+    pub fn method(&self, name: &str) -> Option<ClosureObj> {
+        match self.0.methods.get(name) {
+            Some(method) => Some(method.clone()),
+            None => self.0.parent.as_ref()?.method(name),
+        }
+    }
+}
+
+impl Display for ClassObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<lớp {}>", self.0.name)
+    }
+}
+
+/// An object made by calling a class. Its fields are shared by every copy
+/// of it, and are created by assigning to them.
+#[derive(Clone)]
+pub struct InstanceObj {
+    pub class: ClassObj,
+    pub fields: Rc<RefCell<HashMap<Rc<str>, ValueObj>>>,
+}
+
+impl InstanceObj {
+    pub fn new(class: ClassObj) -> Self {
+        InstanceObj {
+            class,
+            fields: Rc::default(),
+        }
+    }
+}
+
+impl Display for InstanceObj {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<đối tượng {}>", self.class.0.name)
+    }
+}
+
+pub type ValueIter = Box<dyn Iterator<Item = ValueObj>>;
+
 #[derive(Clone)]
 pub enum ValueObj {
     Undefined,
-    Infinity { positive: bool },
+    Integer(IntegerObj),
     Numerical(NumericalObj),
+    Boolean(BooleanObj),
     String(StringObj),
     Tuple(TupleObj),
+    Range(RangeObj),
+    List(ListObj),
+    Dict(DictObj),
+    Function(ClosureObj),
+    Builtin(BuiltinObj),
+    Method(MethodObj),
+    Class(ClassObj),
+    Instance(InstanceObj),
+    Exception(ExceptionObj),
 }
 
 impl Display for ValueObj {
@@ -50,30 +468,339 @@ impl Display for ValueObj {
             "{}",
             match self {
                 ValueObj::Undefined => "undefined".to_string(),
+                ValueObj::Integer(int) => format!("{}", int),
                 ValueObj::Numerical(num) => format!("{}", num),
-                ValueObj::Infinity { positive } => {
-                    if *positive {
-                        "+inf".to_string()
-                    } else {
-                        "-inf".to_string()
-                    }
-                }
+                ValueObj::Boolean(b) => format!("{}", b),
                 ValueObj::String(s) => format!("{}", s),
                 ValueObj::Tuple(t) => format!("{}", t),
+                ValueObj::Range(r) => format!("{}", r),
+                ValueObj::List(l) => format!("{}", l),
+                ValueObj::Dict(d) => format!("{}", d),
+                ValueObj::Function(func) => format!("{}", func),
+                ValueObj::Builtin(builtin) => format!("{}", builtin),
+                ValueObj::Method(method) => format!("{}", method),
+                ValueObj::Class(class) => format!("{}", class),
+                ValueObj::Instance(instance) => format!("{}", instance),
+                ValueObj::Exception(exception) => format!("{}", exception),
             }
         )
     }
 }
 
+/// Values of different kinds are never equal, though integers and floats
+/// compare by their numeric value. Functions are only equal to themselves.
+impl PartialEq for ValueObj {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ValueObj::Undefined, ValueObj::Undefined) => true,
+            (ValueObj::Boolean(b1), ValueObj::Boolean(b2)) => b1.0 == b2.0,
+            (ValueObj::String(s1), ValueObj::String(s2)) => s1.0 == s2.0,
+            (ValueObj::Tuple(t1), ValueObj::Tuple(t2)) => t1.0 == t2.0,
+            (ValueObj::Range(r1), ValueObj::Range(r2)) => {
+                r1.start == r2.start && r1.stop == r2.stop && r1.step == r2.step
+            }
+            (ValueObj::List(l1), ValueObj::List(l2)) => {
+                Rc::ptr_eq(&l1.0, &l2.0)
+                    || compare_once(
+                        Rc::as_ptr(&l1.0).cast(),
+                        Rc::as_ptr(&l2.0).cast(),
+                        true,
+                        || *l1.0.borrow() == *l2.0.borrow(),
+                    )
+            }
+            (ValueObj::Dict(d1), ValueObj::Dict(d2)) => {
+                Rc::ptr_eq(&d1.0, &d2.0)
+                    || compare_once(
+                        Rc::as_ptr(&d1.0).cast(),
+                        Rc::as_ptr(&d2.0).cast(),
+                        true,
+                        || {
+                            let (d1, d2) = (d1.0.borrow(), d2.0.borrow());
+                            d1.len() == d2.len()
+                                && d1.indices.iter().all(|(key, &i)| {
+                                    d2.get(key).is_some_and(|value| *value == d1.entries[i].1)
+                                })
+                        },
+                    )
+            }
+            (ValueObj::Function(f1), ValueObj::Function(f2)) => {
+                Rc::ptr_eq(&f1.def, &f2.def) && f1.env.ptr_eq(&f2.env)
+            }
+            (ValueObj::Builtin(b1), ValueObj::Builtin(b2)) => b1.name == b2.name,
+            (ValueObj::Method(m1), ValueObj::Method(m2)) => {
+                m1.func.name() == m2.func.name() && m1.receiver == m2.receiver
+            }
+            (ValueObj::Class(c1), ValueObj::Class(c2)) => Rc::ptr_eq(&c1.0, &c2.0),
+            (ValueObj::Instance(i1), ValueObj::Instance(i2)) => Rc::ptr_eq(&i1.fields, &i2.fields),
+            (ValueObj::Exception(e1), ValueObj::Exception(e2)) => {
+                e1.kind == e2.kind && e1.message == e2.message
+            }
+            _ => self.compare(other) == Some(Ordering::Equal),
+        }
+    }
+}
+
 impl ValueObj {
-    fn to_string(&self) -> String {
+    /// Undefined values, zero and empty strings, tuples, ranges, lists or
+    /// dictionaries are falsy, every other value is truthy.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            ValueObj::Undefined => false,
+            ValueObj::Integer(int) => !int.0.is_zero(),
+            ValueObj::Numerical(num) => num.0 != 0.,
+            ValueObj::Boolean(b) => b.0,
+            ValueObj::String(s) => !s.0.is_empty(),
+            ValueObj::Tuple(t) => !t.0.is_empty(),
+            ValueObj::Range(r) => !r.is_empty(),
+            ValueObj::List(l) => !l.0.borrow().is_empty(),
+            ValueObj::Dict(d) => !d.0.borrow().is_empty(),
+            ValueObj::Function(_)
+            | ValueObj::Builtin(_)
+            | ValueObj::Method(_)
+            | ValueObj::Class(_)
+            | ValueObj::Instance(_)
+            | ValueObj::Exception(_) => true,
+        }
+    }
+
+    /// Produces the elements of an iterable value in order. Strings yield
+    /// each of their characters as a string. A list yields the elements it
+    /// has when the iteration starts, and a dictionary its keys.
+    pub fn iterate(&self, eval: &Evaluator) -> Result<ValueIter, Diag> {
+        match self {
+            ValueObj::Tuple(t) => Ok(Box::new(t.0.clone().into_iter())),
+            ValueObj::Range(r) => Ok(r.iter()),
+            ValueObj::List(l) => Ok(Box::new(l.0.borrow().clone().into_iter())),
+            ValueObj::Dict(d) => {
+                let keys: Vec<ValueObj> =
+                    d.0.borrow()
+                        .entries()
+                        .iter()
+                        .map(|(key, _)| key.clone())
+                        .collect();
+                Ok(Box::new(keys.into_iter()))
+            }
+            ValueObj::String(s) => {
+                let chars: Vec<ValueObj> =
+                    s.0.chars()
+                        .map(|c| ValueObj::String(StringObj(c.to_string().into())))
+                        .collect();
+                Ok(Box::new(chars.into_iter()))
+            }
+            _ => Err(Diag {
+                line: eval.line(),
+                data: DiagData::EvalError(EvalError::NotIterable {
+                    operand: self.describe(),
+                }),
+            }),
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            ValueObj::Integer(int) => Some(int.0.to_f64()),
+            ValueObj::Numerical(num) => Some(num.0),
+            _ => None,
+        }
+    }
+
+    /// Whether `item` is an element of a tuple, range or list, a key of a
+    /// dictionary or a substring of a string. Other values have no members.
+    pub fn contains(&self, item: &ValueObj) -> Option<bool> {
+        match (self, item) {
+            (ValueObj::Dict(d), _) => Some(d.0.borrow().get(&item.try_hash_key()?).is_some()),
+            (ValueObj::Tuple(t), _) => Some(t.0.contains(item)),
+            (ValueObj::Range(r), ValueObj::Integer(int)) => Some(r.contains(&int.0)),
+            (ValueObj::Range(r), ValueObj::Numerical(num)) => Some(
+                BigInt::from_f64(num.0).is_some_and(|int| num.0.fract() == 0. && r.contains(&int)),
+            ),
+            (ValueObj::Range(_), _) => Some(false),
+            (ValueObj::List(l), _) => Some(l.0.borrow().contains(item)),
+            (ValueObj::String(s), ValueObj::String(sub)) => Some(s.0.contains(&*sub.0)),
+            _ => None,
+        }
+    }
+
+    fn try_hash_key(&self) -> Option<HashKey> {
+        match self {
+            ValueObj::Integer(int) => Some(HashKey::Integer(int.0.clone())),
+            ValueObj::Numerical(num) => Some(match BigInt::from_f64(num.0) {
+                Some(int) if num.0.fract() == 0. => HashKey::Integer(int),
+                _ => HashKey::Float(num.0.to_bits()),
+            }),
+            ValueObj::Boolean(b) => Some(HashKey::Boolean(b.0)),
+            ValueObj::String(s) => Some(HashKey::String(Rc::clone(&s.0))),
+            ValueObj::Tuple(t) => Some(HashKey::Tuple(
+                t.0.iter()
+                    .map(ValueObj::try_hash_key)
+                    .collect::<Option<_>>()?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// The key a dictionary stores this value under. Only numbers, strings,
+    /// booleans and tuples of those can be keys, as the other values are
+    /// mutable or have no meaningful equality.
+    pub fn hash_key(&self, eval: &Evaluator) -> Result<HashKey, Diag> {
+        self.try_hash_key().ok_or_else(|| Diag {
+            line: eval.line(),
+            data: DiagData::EvalError(EvalError::Unhashable {
+                operand: self.describe(),
+            }),
+        })
+    }
+
+    /// Takes the elements of a tuple, list or string from `start` up to but
+    /// excluding `stop`, every `step` of them. Omitted bounds are undefined
+    /// and default to the whole sequence, in reverse for a negative step.
+    pub fn slice(
+        &self,
+        eval: &Evaluator,
+        start: &ValueObj,
+        stop: &ValueObj,
+        step: &ValueObj,
+    ) -> Result<ValueObj, Diag> {
+        let undefined = |operand: &ValueObj| Diag {
+            line: eval.line(),
+            data: DiagData::EvalError(EvalError::UndefinedOperation {
+                op: OperationKind::Index,
+                operand: format!("{} and {}", self.describe(), operand.describe()),
+            }),
+        };
+        let bound = |value: &ValueObj| match value {
+            ValueObj::Undefined => Ok(None),
+            ValueObj::Integer(int) => Ok(Some(int.0.to_i64_saturating())),
+            _ => Err(undefined(value)),
+        };
+        let (start, stop) = (bound(start)?, bound(stop)?);
+        let step = bound(step)?.unwrap_or(1);
+        if step == 0 {
+            return Err(Diag {
+                line: eval.line(),
+                data: DiagData::EvalError(EvalError::ZeroSliceStep),
+            });
+        }
+        let select = |len: usize| {
+            let len = len as i64;
+            let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+            let clamp = |bound: i64| match bound < 0 {
+                true => bound.saturating_add(len).max(lower),
+                false => bound.min(upper),
+            };
+            let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+            let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+            let mut indices = Vec::new();
+            let mut i = start;
+            while (step > 0 && i < stop) || (step < 0 && i > stop) {
+                indices.push(i as usize);
+                i = i.saturating_add(step);
+            }
+            indices
+        };
+        match self {
+            ValueObj::Tuple(t) => Ok(ValueObj::Tuple(TupleObj(
+                select(t.0.len())
+                    .into_iter()
+                    .map(|i| t.0[i].clone())
+                    .collect(),
+            ))),
+            ValueObj::List(l) => {
+                let elements = l.0.borrow();
+                Ok(ValueObj::List(ListObj::new(
+                    select(elements.len())
+                        .into_iter()
+                        .map(|i| elements[i].clone())
+                        .collect(),
+                )))
+            }
+            ValueObj::String(s) => {
+                let chars: Vec<char> = s.0.chars().collect();
+                let sliced: String = select(chars.len()).into_iter().map(|i| chars[i]).collect();
+                Ok(ValueObj::String(StringObj(sliced.into())))
+            }
+            _ => Err(undefined(&ValueObj::Undefined)),
+        }
+    }
+
+    /// Orders numbers, strings, tuples and lists among their own kind;
+    /// tuples and lists are ordered lexicographically. Other pairs of values
+    /// are not ordered.
+    pub fn compare(&self, other: &ValueObj) -> Option<Ordering> {
+        match (self, other) {
+            (ValueObj::Integer(i1), ValueObj::Integer(i2)) => Some(i1.0.cmp(&i2.0)),
+            (ValueObj::Integer(int), _) => int.0.compare_f64(other.as_f64()?),
+            (_, ValueObj::Integer(int)) => Some(int.0.compare_f64(self.as_f64()?)?.reverse()),
+            (ValueObj::String(s1), ValueObj::String(s2)) => Some(s1.0.cmp(&s2.0)),
+            (ValueObj::Tuple(t1), ValueObj::Tuple(t2)) => compare_sequences(&t1.0, &t2.0),
+            (ValueObj::List(l1), ValueObj::List(l2)) => compare_once(
+                Rc::as_ptr(&l1.0).cast(),
+                Rc::as_ptr(&l2.0).cast(),
+                Some(Ordering::Equal),
+                || compare_sequences(&l1.0.borrow(), &l2.0.borrow()),
+            ),
+            _ => self.as_f64()?.partial_cmp(&other.as_f64()?),
+        }
+    }
+
+    /// Formats the value as it appears inside a collection, where strings
+    /// are quoted.
+    pub fn repr(&self) -> String {
+        match self {
+            ValueObj::String(s) => format!("{:?}", s.0),
+            _ => self.to_string(),
+        }
+    }
+
+    pub(crate) fn describe(&self) -> String {
         match self {
             Self::Undefined => "an undefined value".to_string(),
-            ValueObj::Numerical(_) | ValueObj::Infinity { .. } => "a number".to_string(),
+            ValueObj::Integer(_) => "an integer".to_string(),
+            ValueObj::Numerical(_) => "a number".to_string(),
+            ValueObj::Boolean(_) => "a boolean".to_string(),
             ValueObj::String(_) => "a string".to_string(),
             ValueObj::Tuple(_) => "a tuple".to_string(),
+            ValueObj::Range(_) => "a range".to_string(),
+            ValueObj::List(_) => "a list".to_string(),
+            ValueObj::Dict(_) => "a dictionary".to_string(),
+            ValueObj::Function(_) | ValueObj::Builtin(_) | ValueObj::Method(_) => {
+                "a function".to_string()
+            }
+            ValueObj::Class(_) => "a class".to_string(),
+            ValueObj::Instance(instance) => format!("an instance of {}", instance.class.0.name),
+            ValueObj::Exception(_) => "an exception".to_string(),
+        }
+    }
+}
+
+thread_local! {
+    /// Pairs of lists or dictionaries being compared, so that comparing ones
+    /// that contain themselves ends rather than recursing forever.
+    static COMPARED: RefCell<Vec<(*const (), *const ())>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Compares two lists or dictionaries with `compare`, unless they are
+/// already being compared further up. Nothing found so far tells them apart
+/// then, so they are taken to be `same`.
+fn compare_once<T>(c1: *const (), c2: *const (), same: T, compare: impl FnOnce() -> T) -> T {
+    let pair = (c1, c2);
+    if COMPARED.with_borrow(|pairs| pairs.contains(&pair)) {
+        return same;
+    }
+    COMPARED.with_borrow_mut(|pairs| pairs.push(pair));
+    let result = compare();
+    COMPARED.with_borrow_mut(|pairs| pairs.pop());
+    result
+}
+
+fn compare_sequences(s1: &[ValueObj], s2: &[ValueObj]) -> Option<Ordering> {
+    for (v1, v2) in s1.iter().zip(s2) {
+        match v1.compare(v2)? {
+            Ordering::Equal => {}
+            ordering => return Some(ordering),
         }
     }
+    Some(s1.len().cmp(&s2.len()))
 }
 
 #[derive(Clone, Copy)]
@@ -82,7 +809,18 @@ pub enum OperationKind {
     Subtract,
     Multiply,
     Divide,
+    FloorDivide,
+    Modulo,
+    Power,
     NegativePrefix,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    In,
+    Index,
 }
 
 impl Display for OperationKind {
@@ -92,7 +830,18 @@ impl Display for OperationKind {
             OperationKind::Subtract => "subtract",
             OperationKind::Multiply => "multiply",
             OperationKind::Divide => "divide",
+            OperationKind::FloorDivide => "floor divide",
+            OperationKind::Modulo => "take the remainder",
+            OperationKind::Power => "raise to a power",
             OperationKind::NegativePrefix => "negative prefix",
+            OperationKind::Equal => "compare equal",
+            OperationKind::NotEqual => "compare not equal",
+            OperationKind::Less => "compare less than",
+            OperationKind::LessEqual => "compare less than or equal",
+            OperationKind::Greater => "compare greater than",
+            OperationKind::GreaterEqual => "compare greater than or equal",
+            OperationKind::In => "test membership",
+            OperationKind::Index => "index",
         };
         write!(f, "{}", op_str)
     }
@@ -103,97 +852,296 @@ pub struct Operation {
     pub operands: (ValueObj, ValueObj),
 }
 
-impl Evaluable for Operation {
-    fn evaluate(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
-        match &self.kind {
-            OperationKind::Add => Ok(ValueObj::Numerical(NumericalObj(match &self.operands {
-                (ValueObj::Numerical(op1), ValueObj::Numerical(op2)) => op1.0 + op2.0,
-                _ => {
-                    return Err(Diag {
-                        line: eval.cur_line(),
-                        data: DiagData::EvalError(EvalError::UndefinedOperation {
-                            op: self.kind,
-                            operand: format!(
-                                "{} and {}",
-                                self.operands.0.to_string(),
-                                self.operands.1.to_string()
-                            ),
-                        }),
-                    });
+/// Numeric operands brought to a common type: integers stay exact unless
+/// one of them is a float, which promotes both to floats.
+enum NumericOperands<'a> {
+    Integers(&'a BigInt, &'a BigInt),
+    Floats(f64, f64),
+}
+
+impl NumericOperands<'_> {
+    fn into_floats(self) -> (f64, f64) {
+        match self {
+            NumericOperands::Integers(i1, i2) => (i1.to_f64(), i2.to_f64()),
+            NumericOperands::Floats(f1, f2) => (f1, f2),
+        }
+    }
+}
+
+impl Operation {
+    fn numeric_operands(&self) -> Option<NumericOperands<'_>> {
+        let as_float = |value: &ValueObj| match value {
+            ValueObj::Integer(int) => Some(int.0.to_f64()),
+            ValueObj::Numerical(num) => Some(num.0),
+            _ => None,
+        };
+        match &self.operands {
+            (ValueObj::Integer(i1), ValueObj::Integer(i2)) => {
+                Some(NumericOperands::Integers(&i1.0, &i2.0))
+            }
+            (op1, op2) => Some(NumericOperands::Floats(as_float(op1)?, as_float(op2)?)),
+        }
+    }
+
+    /// Adds, subtracts, multiplies or raises two numbers to a power. A
+    /// negative integer exponent gives a float, as the result is a fraction,
+    /// and an integer power too large to compute in reasonable time is an
+    /// error.
+    fn evaluate_arithmetic(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+        let Some(operands) = self.numeric_operands() else {
+            return Err(self.undefined(eval));
+        };
+        if let NumericOperands::Integers(i1, i2) = operands
+            && (!matches!(self.kind, OperationKind::Power) || !i2.is_negative())
+        {
+            return Ok(ValueObj::Integer(IntegerObj(match self.kind {
+                OperationKind::Add => i1 + i2,
+                OperationKind::Subtract => i1 - i2,
+                OperationKind::Multiply => i1 * i2,
+                _ => i1.checked_pow(i2, MAX_INTEGER_BITS).ok_or_else(|| Diag {
+                    line: eval.line(),
+                    data: DiagData::EvalError(EvalError::IntegerTooLarge {
+                        bits: MAX_INTEGER_BITS,
+                    }),
+                })?,
+            })));
+        }
+        let (f1, f2) = operands.into_floats();
+        if matches!(self.kind, OperationKind::Power) && f1 == 0. && f2 < 0. {
+            // Zero to a negative power divides by zero.
+            self.check_division_by_zero(eval)?;
+        }
+        Ok(ValueObj::Numerical(NumericalObj(match self.kind {
+            OperationKind::Add => f1 + f2,
+            OperationKind::Subtract => f1 - f2,
+            OperationKind::Multiply => f1 * f2,
+            _ => f1.powf(f2),
+        })))
+    }
+
+    /// Raises `DivisionByZero` if the evaluator is set to, rather than let
+    /// the operation give an infinity or NaN.
+    fn check_division_by_zero(&self, eval: &Evaluator) -> Result<(), Diag> {
+        match eval.division_by_zero() {
+            DivisionByZero::Ieee754 => Ok(()),
+            DivisionByZero::Error => Err(Diag {
+                line: eval.line(),
+                data: DiagData::EvalError(EvalError::DivisionByZero),
+            }),
+        }
+    }
+
+    /// Divides two numbers, rounding the quotient down for a floor division
+    /// or keeping the remainder, which has the sign of the divisor, for a
+    /// modulo. Dividing a float by zero, or anything with `/`, follows IEEE
+    /// 754 unless the evaluator is set to raise an error. A floor division
+    /// or modulo of two integers gives an integer, which has no infinity or
+    /// NaN, so by zero it is always an error.
+    fn evaluate_division(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+        let Some(operands) = self.numeric_operands() else {
+            return Err(self.undefined(eval));
+        };
+        if let NumericOperands::Integers(i1, i2) = operands
+            && !matches!(self.kind, OperationKind::Divide)
+        {
+            let Some((quotient, remainder)) = i1.div_rem_floor(i2) else {
+                return Err(Diag {
+                    line: eval.line(),
+                    data: DiagData::EvalError(EvalError::DivisionByZero),
+                });
+            };
+            return Ok(ValueObj::Integer(IntegerObj(match self.kind {
+                OperationKind::FloorDivide => quotient,
+                _ => remainder,
+            })));
+        }
+        let (f1, f2) = operands.into_floats();
+        if f2 == 0. {
+            self.check_division_by_zero(eval)?;
+        }
+        Ok(ValueObj::Numerical(NumericalObj(match self.kind {
+            OperationKind::Divide => f1 / f2,
+            OperationKind::FloorDivide => (f1 / f2).floor(),
+            _ => {
+                let remainder = f1 % f2;
+                if remainder != 0. && (remainder < 0.) != (f2 < 0.) {
+                    remainder + f2
+                } else {
+                    remainder
                 }
-            }))),
-            OperationKind::Subtract => {
-                Ok(ValueObj::Numerical(NumericalObj(match &self.operands {
-                    (ValueObj::Numerical(op1), ValueObj::Numerical(op2)) => op1.0 - op2.0,
-                    _ => {
-                        return Err(Diag {
-                            line: eval.cur_line(),
-                            data: DiagData::EvalError(EvalError::UndefinedOperation {
-                                op: self.kind,
-                                operand: format!(
-                                    "{} and {}",
-                                    self.operands.0.to_string(),
-                                    self.operands.1.to_string()
-                                ),
-                            }),
-                        });
-                    }
-                })))
             }
-            OperationKind::Multiply => {
-                Ok(ValueObj::Numerical(NumericalObj(match &self.operands {
-                    (ValueObj::Numerical(op1), ValueObj::Numerical(op2)) => op1.0 * op2.0,
-                    _ => {
-                        return Err(Diag {
-                            line: eval.cur_line(),
-                            data: DiagData::EvalError(EvalError::UndefinedOperation {
-                                op: self.kind,
-                                operand: format!(
-                                    "{} and {}",
-                                    self.operands.0.to_string(),
-                                    self.operands.1.to_string()
-                                ),
-                            }),
-                        });
-                    }
-                })))
+        })))
+    }
+
+    fn resolve_index(&self, eval: &Evaluator, len: usize) -> Result<usize, Diag> {
+        match &self.operands.1 {
+            ValueObj::Integer(index) => index.resolve_index(eval, len),
+            _ => Err(self.undefined(eval)),
+        }
+    }
+
+    /// Gets an element of a tuple, list or string by its index, or the value
+    /// of a key in a dictionary.
+    fn evaluate_index(&self, eval: &Evaluator) -> Result<ValueObj, Diag> {
+        let (container, index) = &self.operands;
+        match container {
+            ValueObj::Tuple(t) => Ok(t.0[self.resolve_index(eval, t.0.len())?].clone()),
+            ValueObj::List(l) => {
+                let len = l.0.borrow().len();
+                let i = self.resolve_index(eval, len)?;
+                Ok(l.0.borrow()[i].clone())
             }
-            OperationKind::Divide => Ok(ValueObj::Numerical(NumericalObj(match &self.operands {
-                (ValueObj::Numerical(op1), ValueObj::Numerical(op2)) => {
-                    if op2.0 == 0. {
-                        return Ok(ValueObj::Infinity {
-                            positive: op1.0.is_sign_positive(),
-                        });
-                    } else {
-                        op1.0 / op2.0
+            ValueObj::String(s) => {
+                let i = self.resolve_index(eval, s.0.chars().count())?;
+                let c = s.0.chars().nth(i).unwrap_or_default();
+                Ok(ValueObj::String(StringObj(c.to_string().into())))
+            }
+            ValueObj::Dict(d) => d.get(eval, index),
+            _ => Err(self.undefined(eval)),
+        }
+    }
+
+    /// Repeats a string `count` times, unless the result would take more
+    /// than `MAX_STRING_BYTES`.
+    fn repeat_string(
+        &self,
+        eval: &Evaluator,
+        s: &StringObj,
+        count: usize,
+    ) -> Result<ValueObj, Diag> {
+        match count.checked_mul(s.0.len()) {
+            Some(bytes) if bytes <= MAX_STRING_BYTES => {
+                Ok(ValueObj::String(StringObj(s.0.repeat(count).into())))
+            }
+            _ => Err(Diag {
+                line: eval.line(),
+                data: DiagData::EvalError(EvalError::StringTooLong {
+                    bytes: MAX_STRING_BYTES,
+                }),
+            }),
+        }
+    }
+
+    fn undefined(&self, eval: &Evaluator) -> Diag {
+        Diag {
+            line: eval.line(),
+            data: DiagData::EvalError(EvalError::UndefinedOperation {
+                op: self.kind,
+                operand: match self.kind {
+                    OperationKind::NegativePrefix => self.operands.1.describe(),
+                    _ => format!(
+                        "{} and {}",
+                        self.operands.0.describe(),
+                        self.operands.1.describe()
+                    ),
+                },
+            }),
+        }
+    }
+}
+
+/// Sets an element of a list by its index, or the value of a key in a
+/// dictionary. Tuples and strings cannot be changed.
+pub fn assign_index(
+    eval: &Evaluator,
+    container: &ValueObj,
+    index: ValueObj,
+    value: ValueObj,
+) -> Result<(), Diag> {
+    match container {
+        ValueObj::List(l) => {
+            let len = l.0.borrow().len();
+            let i = match &index {
+                ValueObj::Integer(i) => i.resolve_index(eval, len)?,
+                _ => {
+                    return Err(Operation {
+                        kind: OperationKind::Index,
+                        operands: (container.clone(), index),
                     }
+                    .undefined(eval));
                 }
-                _ => {
-                    return Err(Diag {
-                        line: eval.cur_line(),
-                        data: DiagData::EvalError(EvalError::UndefinedOperation {
-                            op: self.kind,
-                            operand: format!(
-                                "{} and {}",
-                                self.operands.0.to_string(),
-                                self.operands.1.to_string()
-                            ),
-                        }),
-                    });
+            };
+            l.0.borrow_mut()[i] = value;
+            Ok(())
+        }
+        ValueObj::Dict(d) => d.insert(eval, index, value),
+        _ => Err(Diag {
+            line: eval.line(),
+            data: DiagData::EvalError(EvalError::IndexNotAssignable {
+                operand: container.describe(),
+            }),
+        }),
+    }
+}
+
+impl Evaluable for Operation {
+    fn evaluate(&self, eval: &mut Evaluator) -> Result<ValueObj, Diag> {
+        let (op1, op2) = &self.operands;
+        match self.kind {
+            OperationKind::Add => match (op1, op2) {
+                (ValueObj::String(s1), ValueObj::String(s2)) => Ok(ValueObj::String(StringObj(
+                    format!("{}{}", s1.0, s2.0).into(),
+                ))),
+                (ValueObj::List(l1), ValueObj::List(l2)) => {
+                    let mut elements = l1.0.borrow().clone();
+                    elements.extend(l2.0.borrow().iter().cloned());
+                    Ok(ValueObj::List(ListObj::new(elements)))
                 }
-            }))),
-            OperationKind::NegativePrefix => {
-                Ok(ValueObj::Numerical(NumericalObj(match &self.operands {
-                    (ValueObj::Numerical(_), ValueObj::Numerical(op2)) => -op2.0,
-                    _ => {
-                        return Err(Diag {
-                            line: eval.cur_line(),
-                            data: DiagData::EvalError(EvalError::UndefinedOperation {
-                                op: self.kind,
-                                operand: format!("{}", self.operands.1.to_string()),
-                            }),
-                        });
+                _ => self.evaluate_arithmetic(eval),
+            },
+            OperationKind::Multiply => match (op1, op2) {
+                (ValueObj::String(s), ValueObj::Integer(count))
+                | (ValueObj::Integer(count), ValueObj::String(s)) => {
+                    // Negative counts repeat nothing, and those beyond a usize are too long.
+                    let count = match count.0.is_negative() {
+                        true => 0,
+                        false => count.0.to_usize().unwrap_or(usize::MAX),
+                    };
+                    self.repeat_string(eval, s, count)
+                }
+                (ValueObj::String(s), ValueObj::Numerical(count))
+                | (ValueObj::Numerical(count), ValueObj::String(s)) => {
+                    // Negative counts repeat nothing, fractional ones are meaningless.
+                    if count.0.fract() != 0. {
+                        return Err(self.undefined(eval));
+                    }
+                    // The cast saturates, so a huge count is too long.
+                    self.repeat_string(eval, s, count.0.max(0.) as usize)
+                }
+                _ => self.evaluate_arithmetic(eval),
+            },
+            OperationKind::Subtract | OperationKind::Power => self.evaluate_arithmetic(eval),
+            OperationKind::Divide | OperationKind::FloorDivide | OperationKind::Modulo => {
+                self.evaluate_division(eval)
+            }
+            OperationKind::NegativePrefix => match op2 {
+                ValueObj::Integer(op2) => Ok(ValueObj::Integer(IntegerObj(-op2.0.clone()))),
+                ValueObj::Numerical(op2) => Ok(ValueObj::Numerical(NumericalObj(-op2.0))),
+                _ => Err(self.undefined(eval)),
+            },
+            OperationKind::Index => self.evaluate_index(eval),
+            OperationKind::In => match op2.contains(op1) {
+                Some(found) => Ok(ValueObj::Boolean(BooleanObj(found))),
+                None => Err(self.undefined(eval)),
+            },
+            OperationKind::Equal => Ok(ValueObj::Boolean(BooleanObj(op1 == op2))),
+            OperationKind::NotEqual => Ok(ValueObj::Boolean(BooleanObj(op1 != op2))),
+            OperationKind::Less
+            | OperationKind::LessEqual
+            | OperationKind::Greater
+            | OperationKind::GreaterEqual => {
+                let Some(ordering) = op1.compare(op2) else {
+                    // NaN is unordered, so no comparison with it holds.
+                    if self.numeric_operands().is_some() {
+                        return Ok(ValueObj::Boolean(BooleanObj(false)));
                     }
+                    return Err(self.undefined(eval));
+                };
+                Ok(ValueObj::Boolean(BooleanObj(match self.kind {
+                    OperationKind::Less => ordering.is_lt(),
+                    OperationKind::LessEqual => ordering.is_le(),
+                    OperationKind::Greater => ordering.is_gt(),
+                    _ => ordering.is_ge(),
                 })))
             }
         }
@@ -1,37 +1,80 @@
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use crate::obj::ValueObj;
 
-pub struct Scope<'a> {
-    table: HashMap<&'a str, ValueObj>,
+/// A chain of frames, innermost first. Each frame holds the names declared
+/// in one block, and names not found in it are looked up in its parent.
+/// Cloning a scope shares its frames rather than copying them.
+#[derive(Clone, Default)]
+pub struct Scope(Rc<RefCell<Frame>>);
+
+#[derive(Default)]
+struct Frame {
+    table: HashMap<Rc<str>, ValueObj>,
+    parent: Option<Scope>,
 }
 
-impl<'a> Scope<'a> {
+impl Scope {
     pub fn new() -> Self {
-        Scope {
+        Scope::default()
+    }
+
+    /// Makes an empty frame in front of `parent`.
+    pub fn child(parent: &Scope) -> Self {
+        Scope(Rc::new(RefCell::new(Frame {
             table: HashMap::new(),
-        }
+            parent: Some(parent.clone()),
+        })))
+    }
+
+    /// Whether both scopes share the same innermost frame.
+    pub fn ptr_eq(&self, other: &Scope) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    pub fn parent(&self) -> Option<Scope> {
+        self.0.borrow().parent.clone()
     }
 
-    pub fn declare(&mut self, name: &'a str, value: ValueObj) -> bool {
-        if self.table.contains_key(name) {
+    /// Declares `name` in the innermost frame, returning false when that
+    /// frame already has it. A name in an outer frame is shadowed.
+    pub fn declare(&self, name: &str, value: ValueObj) -> bool {
+        let mut frame = self.0.borrow_mut();
+        if frame.table.contains_key(name) {
             false
         } else {
-            self.table.insert(name, value);
+            frame.table.insert(name.into(), value);
             true
         }
     }
 
-    pub fn get(&self, name: &str) -> Option<&ValueObj> {
-        self.table.get(name)
+    /// Looks `name` up from the innermost frame outwards.
+    pub fn get(&self, name: &str) -> Option<ValueObj> {
+        let frame = self.0.borrow();
+        match frame.table.get(name) {
+            Some(value) => Some(value.clone()),
+            None => frame.parent.as_ref()?.get(name),
+        }
     }
 
-    pub fn set(&mut self, name: &'a str, value: ValueObj) -> bool {
-        if self.table.contains_key(name) {
-            self.table.insert(name, value);
-            true
-        } else {
-            false
+    /// Overwrites `name` in the innermost frame that has it, returning false
+    /// when no frame does.
+    pub fn set(&self, name: &str, value: ValueObj) -> bool {
+        let mut frame = self.0.borrow_mut();
+        match frame.table.get_mut(name) {
+            Some(slot) => {
+                *slot = value;
+                true
+            }
+            None => match &frame.parent {
+                Some(parent) => parent.set(name, value),
+                None => false,
+            },
         }
     }
+
+    /// Declares `name` in the innermost frame, or overwrites it there.
+    pub fn bind(&self, name: &str, value: ValueObj) {
+        self.0.borrow_mut().table.insert(name.into(), value);
+    }
 }
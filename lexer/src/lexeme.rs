@@ -5,6 +5,10 @@ pub enum Kind {
     Invalid,
     Eof,
     Eol,
+    Indent,
+    Dedent,
+    MixedIndent,
+    UnalignedDedent,
     WordSpaces,
     Whitespaces,
     Comment,
@@ -12,15 +16,31 @@ pub enum Kind {
     Float,
     Decimal,
     String,
+    UnterminatedString,
+    FStringStart,
+    FStringText,
+    FStringEnd,
     Plus,
     Minus,
     Asterisk,
     Slash,
+    DoubleAsterisk,
+    DoubleSlash,
+    Percent,
     Equal,
+    PlusEqual,
+    MinusEqual,
+    AsteriskEqual,
+    SlashEqual,
     Period,
     Comma,
+    Colon,
     Greater,
     Less,
+    EqualEqual,
+    BangEqual,
+    GreaterEqual,
+    LessEqual,
     LeftBrace,
     RightBrace,
     LeftParen,
@@ -35,6 +55,10 @@ impl Display for Kind {
             Self::Invalid => "an invalid character",
             Self::Eof => "end of file",
             Self::Eol => "end of line",
+            Self::Indent => "an indentation",
+            Self::Dedent => "a dedentation",
+            Self::MixedIndent => "an inconsistent indentation",
+            Self::UnalignedDedent => "an unaligned dedentation",
             Self::WordSpaces => "word spaces",
             Self::Whitespaces => "whitespaces",
             Self::Comment => "a comment",
@@ -42,15 +66,31 @@ impl Display for Kind {
             Self::Float => "a floating point number",
             Self::Decimal => "a decimal number",
             Self::String => "a string",
+            Self::UnterminatedString => "an unterminated string",
+            Self::FStringStart => "the start of a formatted string",
+            Self::FStringText => "the text of a formatted string",
+            Self::FStringEnd => "the end of a formatted string",
             Self::Plus => "a plus sign '+'",
             Self::Minus => "a minus sign '-'",
             Self::Asterisk => "an asterisk '*'",
             Self::Slash => "a slash '/'",
+            Self::DoubleAsterisk => "a double asterisk '**'",
+            Self::DoubleSlash => "a double slash '//'",
+            Self::Percent => "a percent sign '%'",
             Self::Equal => "an equal sign '='",
+            Self::PlusEqual => "a plus-equal sign '+='",
+            Self::MinusEqual => "a minus-equal sign '-='",
+            Self::AsteriskEqual => "an asterisk-equal sign '*='",
+            Self::SlashEqual => "a slash-equal sign '/='",
             Self::Period => "a period '.'",
             Self::Comma => "a comma ','",
+            Self::Colon => "a colon ':'",
             Self::Greater => "a greater than sign '>'",
             Self::Less => "a less than sign '<'",
+            Self::EqualEqual => "a double equal sign '=='",
+            Self::BangEqual => "a not equal sign '!='",
+            Self::GreaterEqual => "a greater than or equal sign '>='",
+            Self::LessEqual => "a less than or equal sign '<='",
             Self::LeftBrace => "a left brace '{'",
             Self::RightBrace => "a right brace '}'",
             Self::LeftParen => "a left parenthesis '('",
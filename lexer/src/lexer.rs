@@ -2,9 +2,26 @@ use std::str::Chars;
 
 use crate::lexeme::{Kind, Lexeme};
 
+/// A formatted string being lexed, e.g. `f"Xin chào {tên}"`.
+struct Interpolation {
+    quote: char,
+    triple: bool,
+    /// Whether the text is being lexed rather than an embedded expression.
+    in_text: bool,
+    /// Bracket depth outside the braces of the embedded expression.
+    bracket_depth: u32,
+}
+
 pub struct Lexer<'a> {
     chars: Chars<'a>,
     cur_len: u32,
+    /// Indentation strings of the enclosing blocks, the outermost being empty.
+    indents: Vec<String>,
+    pending_dedents: u32,
+    at_line_start: bool,
+    /// Nesting depth of brackets, inside which line breaks do not matter.
+    bracket_depth: u32,
+    interpolations: Vec<Interpolation>,
 }
 
 impl<'a> Lexer<'a> {
@@ -12,6 +29,11 @@ impl<'a> Lexer<'a> {
         Self {
             chars: input.chars(),
             cur_len: 0,
+            indents: vec![String::new()],
+            pending_dedents: 0,
+            at_line_start: true,
+            bracket_depth: 0,
+            interpolations: Vec::new(),
         }
     }
 
@@ -179,11 +201,290 @@ impl<'a> Lexer<'a> {
         )
     }
 
+    pub fn check_is_word_char(ch: char) -> bool {
+        Self::check_is_alpha(ch) || ch == '_' || ch.is_ascii_digit()
+    }
+
+    /// Compares the indentation of a new line against the enclosing blocks.
+    /// Blank and comment-only lines never open or close a block.
+    fn lex_indentation(&mut self) -> Option<Kind> {
+        let rest = self.chars.as_str();
+        let indent_len = rest.find(|c| c != ' ' && c != '\t').unwrap_or(rest.len());
+        let (indent, after) = rest.split_at(indent_len);
+        if matches!(after.chars().next(), None | Some('\n' | '\r' | '#')) {
+            return None;
+        }
+        let top = self.indents.last().map_or("", String::as_str);
+        if indent == top {
+            None
+        } else if indent.starts_with(top) {
+            self.indents.push(indent.to_string());
+            self.advance_char_while(|c| c == ' ' || c == '\t');
+            Some(Kind::Indent)
+        } else if top.starts_with(indent) {
+            let mut dedents = 0;
+            while self
+                .indents
+                .last()
+                .is_some_and(|top| top.len() > indent.len())
+            {
+                self.indents.pop();
+                dedents += 1;
+            }
+            self.advance_char_while(|c| c == ' ' || c == '\t');
+            if self.indents.last().map_or("", String::as_str) != indent {
+                return Some(Kind::UnalignedDedent);
+            }
+            // The first dedent holds the indentation, the others are empty.
+            self.pending_dedents = dedents - 1;
+            Some(Kind::Dedent)
+        } else {
+            self.advance_char_while(|c| c == ' ' || c == '\t');
+            Some(Kind::MixedIndent)
+        }
+    }
+
+    /// Consumes the rest of an opening quote, returning whether it is tripled.
+    fn lex_opening_quote(&mut self, quote: char) -> bool {
+        let mut rest = self.chars.clone();
+        let triple = rest.next() == Some(quote) && rest.next() == Some(quote);
+        if triple {
+            self.next_char();
+            self.next_char();
+        }
+        triple
+    }
+
+    /// Consumes a closing quote matching the opening one, if there is one.
+    fn lex_closing_quote(&mut self, quote: char, triple: bool) -> bool {
+        let count = if triple { 3 } else { 1 };
+        let mut rest = self.chars.clone();
+        if !(0..count).all(|_| rest.next() == Some(quote)) {
+            return false;
+        }
+        for _ in 0..count {
+            self.next_char();
+        }
+        true
+    }
+
+    /// Whether `lead` and what follows are the prefix of a string: 'f' for
+    /// a formatted one, 'd' for one whose common indentation is stripped.
+    fn check_string_prefix(&self, lead: char) -> bool {
+        let other = if lead == 'f' { 'd' } else { 'f' };
+        let rest = self.chars.as_str();
+        let rest = rest.strip_prefix(other).unwrap_or(rest);
+        rest.starts_with(['"', '\''])
+    }
+
+    fn lex_prefixed_string(&mut self, lead: char) -> Kind {
+        let mut formatted = lead == 'f';
+        if let Some(c @ ('f' | 'd')) = self.peek_char() {
+            formatted |= c == 'f';
+            self.next_char();
+        }
+        let quote = self.next_char().unwrap_or('"');
+        let triple = self.lex_opening_quote(quote);
+        if formatted {
+            self.interpolations.push(Interpolation {
+                quote,
+                triple,
+                in_text: true,
+                bracket_depth: 0,
+            });
+            Kind::FStringStart
+        } else {
+            self.lex_string(quote, triple)
+        }
+    }
+
+    /// Lexes the rest of a string opened by `quote`. A backslash escapes the
+    /// character after it, and only a triple-quoted string may run past the
+    /// end of its line.
+    fn lex_string(&mut self, quote: char, triple: bool) -> Kind {
+        loop {
+            if self.lex_closing_quote(quote, triple) {
+                return Kind::String;
+            }
+            match self.peek_char() {
+                None => return Kind::UnterminatedString,
+                Some('\n') if !triple => return Kind::UnterminatedString,
+                Some('\\') => {
+                    self.next_char();
+                    match self.peek_char() {
+                        None => {}
+                        Some('\n') if !triple => {}
+                        Some(_) => {
+                            self.next_char();
+                        }
+                    }
+                }
+                Some(_) => {
+                    self.next_char();
+                }
+            }
+        }
+    }
+
+    /// Lexes the text of a formatted string up to an embedded expression or
+    /// its end. Doubled braces stand for literal ones.
+    fn lex_fstring_text(&mut self) -> Kind {
+        let Some((quote, triple)) = self.interpolations.last().map(|i| (i.quote, i.triple)) else {
+            unreachable!("formatted string text is only lexed inside one");
+        };
+        if self.lex_closing_quote(quote, triple) {
+            self.interpolations.pop();
+            return Kind::FStringEnd;
+        }
+        let rest = self.chars.as_str();
+        match rest.chars().next() {
+            None => {
+                self.interpolations.pop();
+                return Kind::UnterminatedString;
+            }
+            Some('\n') if !triple => {
+                self.interpolations.pop();
+                return Kind::UnterminatedString;
+            }
+            Some('{') if !rest.starts_with("{{") => {
+                self.next_char();
+                if let Some(interpolation) = self.interpolations.last_mut() {
+                    interpolation.in_text = false;
+                    interpolation.bracket_depth = self.bracket_depth;
+                }
+                self.bracket_depth += 1;
+                return Kind::LeftBrace;
+            }
+            _ => {}
+        }
+        loop {
+            let mut rest = self.chars.clone();
+            let closing = if triple { 3 } else { 1 };
+            if (0..closing).all(|_| rest.next() == Some(quote)) {
+                break;
+            }
+            let rest = self.chars.as_str();
+            match rest.chars().next() {
+                None => break,
+                Some('\n') if !triple => break,
+                Some('{' | '}') if rest[1..].starts_with(&rest[..1]) => {
+                    self.next_char();
+                    self.next_char();
+                }
+                Some('{') => break,
+                Some('\\') => {
+                    self.next_char();
+                    match self.peek_char() {
+                        None => {}
+                        Some('\n') if !triple => {}
+                        Some(_) => {
+                            self.next_char();
+                        }
+                    }
+                }
+                Some(_) => {
+                    self.next_char();
+                }
+            }
+        }
+        Kind::FStringText
+    }
+
+    /// Lexes a number after its first digit: an integer, possibly with a
+    /// `0x`, `0b` or `0o` radix prefix, or a float with a fraction or an
+    /// exponent. Digits may be grouped with '_'. Misplaced separators and
+    /// digits outside the radix are kept in the lexeme for the parser to
+    /// point at.
+    fn lex_number(&mut self, lead: char) -> Kind {
+        if lead == '0'
+            && self
+                .chars
+                .as_str()
+                .starts_with(['x', 'X', 'b', 'B', 'o', 'O'])
+        {
+            self.advance_char_while(Self::check_is_word_char);
+            return Kind::Decimal;
+        }
+        let is_digit = |c: char| c.is_ascii_digit() || c == '_';
+        self.advance_char_while(is_digit);
+        let mut kind = Kind::Decimal;
+        if self.peek_char() == Some('.') {
+            self.next_char();
+            self.advance_char_while(is_digit);
+            kind = Kind::Float;
+        }
+        let rest = self.chars.as_str();
+        if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+            // A sign belongs to the exponent only if digits follow it, and an
+            // exponent without digits is left for the parser to report.
+            let digits = exponent
+                .strip_prefix(['+', '-'])
+                .filter(|digits| digits.starts_with(is_digit))
+                .unwrap_or(exponent);
+            for _ in 0..rest.len() - digits.len() {
+                self.next_char();
+            }
+            self.advance_char_while(is_digit);
+            kind = Kind::Float;
+        }
+        // Letters right after a number, as in `123abc`, make the whole word a
+        // malformed number rather than a number followed by a name.
+        self.advance_char_while(Self::check_is_word_char);
+        kind
+    }
+
+    /// Lexes an operator that may be followed by '=' to form a compound one.
+    fn lex_compound(&mut self, single: Kind, compound: Kind) -> Kind {
+        match self.peek_char() {
+            Some('=') => {
+                self.next_char();
+                compound
+            }
+            _ => single,
+        }
+    }
+
+    /// Whether the last lexeme is inside brackets, where a line break does
+    /// not end the line.
+    pub fn in_brackets(&self) -> bool {
+        self.bracket_depth > 0
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Lexeme {
         self.cur_len = 0;
+        if self.pending_dedents > 0 {
+            self.pending_dedents -= 1;
+            return Lexeme {
+                kind: Kind::Dedent,
+                len: 0,
+            };
+        }
+        if self.interpolations.last().is_some_and(|i| i.in_text) {
+            return Lexeme {
+                kind: self.lex_fstring_text(),
+                len: self.cur_len,
+            };
+        }
+        if self.at_line_start {
+            self.at_line_start = false;
+            if let Some(kind) = self.lex_indentation() {
+                return Lexeme {
+                    kind,
+                    len: self.cur_len,
+                };
+            }
+        }
         let kind = match self.next_char() {
+            None if self.indents.len() > 1 => {
+                self.indents.pop();
+                Kind::Dedent
+            }
             None => Kind::Eof,
-            Some('\n') => Kind::Eol,
+            Some('\n') => {
+                self.at_line_start = self.bracket_depth == 0;
+                Kind::Eol
+            }
             Some(' ') => {
                 while let Some(' ') = self.peek_char() {
                     self.next_char();
@@ -198,43 +499,67 @@ impl<'a> Lexer<'a> {
                 self.advance_char_while(|c| c.is_whitespace() && c != ' ' && c != '\n');
                 Kind::Whitespaces
             }
+            Some(lead @ ('f' | 'd')) if self.check_string_prefix(lead) => {
+                self.lex_prefixed_string(lead)
+            }
             Some(lead) if Self::check_is_alpha(lead) || lead == '_' => {
-                self.advance_char_while(|c| {
-                    Self::check_is_alpha(c) || c == '_' || matches!(c, '0'..='9')
-                });
+                self.advance_char_while(Self::check_is_word_char);
                 Kind::Word
             }
             Some(quote @ ('"' | '\'')) => {
-                self.advance_char_while(|c| c != quote);
+                let triple = self.lex_opening_quote(quote);
+                self.lex_string(quote, triple)
+            }
+            Some(lead @ '0'..='9') => self.lex_number(lead),
+            Some('+') => self.lex_compound(Kind::Plus, Kind::PlusEqual),
+            Some('-') => self.lex_compound(Kind::Minus, Kind::MinusEqual),
+            Some('*') if self.peek_char() == Some('*') => {
                 self.next_char();
-                Kind::String
-            }
-            Some('0'..='9') => {
-                self.advance_char_while(|c| matches!(c, '0'..='9'));
-                match self.peek_char() {
-                    Some('.') => {
-                        self.next_char();
-                        self.advance_char_while(|c| matches!(c, '0'..='9'));
-                        Kind::Float
-                    }
-                    _ => Kind::Decimal,
-                }
+                Kind::DoubleAsterisk
+            }
+            Some('*') => self.lex_compound(Kind::Asterisk, Kind::AsteriskEqual),
+            Some('/') if self.peek_char() == Some('/') => {
+                self.next_char();
+                Kind::DoubleSlash
             }
-            Some('+') => Kind::Plus,
-            Some('-') => Kind::Minus,
-            Some('*') => Kind::Asterisk,
-            Some('/') => Kind::Slash,
-            Some('=') => Kind::Equal,
+            Some('/') => self.lex_compound(Kind::Slash, Kind::SlashEqual),
+            Some('%') => Kind::Percent,
+            Some('=') => self.lex_compound(Kind::Equal, Kind::EqualEqual),
+            Some('!') => self.lex_compound(Kind::Invalid, Kind::BangEqual),
             Some('.') => Kind::Period,
             Some(',') => Kind::Comma,
-            Some('>') => Kind::Greater,
-            Some('<') => Kind::Less,
-            Some('{') => Kind::LeftBrace,
-            Some('}') => Kind::RightBrace,
-            Some('(') => Kind::LeftParen,
-            Some(')') => Kind::RightParen,
-            Some('[') => Kind::LeftBracket,
-            Some(']') => Kind::RightBracket,
+            Some(':') => Kind::Colon,
+            Some('>') => self.lex_compound(Kind::Greater, Kind::GreaterEqual),
+            Some('<') => self.lex_compound(Kind::Less, Kind::LessEqual),
+            Some('{') => {
+                self.bracket_depth += 1;
+                Kind::LeftBrace
+            }
+            Some('}') => {
+                self.bracket_depth = self.bracket_depth.saturating_sub(1);
+                if let Some(interpolation) = self.interpolations.last_mut()
+                    && self.bracket_depth == interpolation.bracket_depth
+                {
+                    interpolation.in_text = true;
+                }
+                Kind::RightBrace
+            }
+            Some('(') => {
+                self.bracket_depth += 1;
+                Kind::LeftParen
+            }
+            Some(')') => {
+                self.bracket_depth = self.bracket_depth.saturating_sub(1);
+                Kind::RightParen
+            }
+            Some('[') => {
+                self.bracket_depth += 1;
+                Kind::LeftBracket
+            }
+            Some(']') => {
+                self.bracket_depth = self.bracket_depth.saturating_sub(1);
+                Kind::RightBracket
+            }
             Some(_) => Kind::Invalid,
         };
         Lexeme {
@@ -35,6 +35,7 @@ pub mod tests {
     use crate::{lexeme::Kind, lexer::Lexer};
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn always_pass() {
         assert!(true);
     }
@@ -50,7 +51,7 @@ pub mod tests {
     #[test]
     fn whitespaces() {
         let mut lexer = Lexer::new("\t\r\u{2003}");
-        assert_lexer_lexeme!(lexer, Kind::Whitespaces, 3);
+        assert_lexer_lexeme!(lexer, Kind::Whitespaces, 5); // lengths are in bytes
         assert_lexer_eof!(lexer);
     }
 
@@ -71,7 +72,7 @@ pub mod tests {
     #[test]
     fn word_with_unicode() {
         let mut lexer = Lexer::new("xin_chào123");
-        assert_lexer_lexeme!(lexer, Kind::Word, 11);
+        assert_lexer_lexeme!(lexer, Kind::Word, 12);
         assert_lexer_eof!(lexer);
     }
 
@@ -111,7 +112,7 @@ pub mod tests {
 
     #[test]
     fn invalid() {
-        let mut lexer = Lexer::new("@$%");
+        let mut lexer = Lexer::new("@$?");
         assert_lexer_lexeme!(lexer, Kind::Invalid, 1);
         assert_lexer_lexeme!(lexer, Kind::Invalid, 1);
         assert_lexer_lexeme!(lexer, Kind::Invalid, 1);
@@ -126,4 +127,322 @@ pub mod tests {
         assert_lexer_lexeme!(lexer, Kind::Word, 13); // variable_name
         assert_lexer_eof!(lexer);
     }
+
+    #[test]
+    fn indent_and_dedent() {
+        let mut lexer = Lexer::new("a\n  b\n    c\nd");
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 2);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 4);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Dedent, 0);
+        assert_lexer_lexeme!(lexer, Kind::Dedent, 0);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn dedent_to_outer_block() {
+        let mut lexer = Lexer::new("a\n  b\n    c\n  d");
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 2);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 4);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Dedent, 2);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Dedent, 0);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn dedents_hold_indentation_before_token() {
+        let mut lexer = Lexer::new("a\n  b\n      c\n  d e");
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 2);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 6);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Dedent, 2);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Dedent, 0);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn blank_and_comment_lines_keep_indentation() {
+        let mut lexer = Lexer::new("a\n\tb\n\n# note\n\tc");
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Comment, 6);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Whitespaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Dedent, 0);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn no_indentation_inside_brackets() {
+        let mut lexer = Lexer::new("(a,\n  b)");
+        assert_lexer_lexeme!(lexer, Kind::LeftParen, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Comma, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 2);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::RightParen, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn mixed_indent() {
+        let mut lexer = Lexer::new("a\n\tb\n    c");
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::MixedIndent, 4);
+    }
+
+    #[test]
+    fn unaligned_dedent() {
+        let mut lexer = Lexer::new("a\n    b\n  c");
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Indent, 4);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::UnalignedDedent, 2);
+    }
+
+    #[test]
+    fn compound_assignment_operators() {
+        let mut lexer = Lexer::new("+=-=*=/=+-");
+        assert_lexer_lexeme!(lexer, Kind::PlusEqual, 2);
+        assert_lexer_lexeme!(lexer, Kind::MinusEqual, 2);
+        assert_lexer_lexeme!(lexer, Kind::AsteriskEqual, 2);
+        assert_lexer_lexeme!(lexer, Kind::SlashEqual, 2);
+        assert_lexer_lexeme!(lexer, Kind::Plus, 1);
+        assert_lexer_lexeme!(lexer, Kind::Minus, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn comparison_operators() {
+        let mut lexer = Lexer::new("== != <= >= < > = !");
+        assert_lexer_lexeme!(lexer, Kind::EqualEqual, 2);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::BangEqual, 2);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::LessEqual, 2);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::GreaterEqual, 2);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Less, 1);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Greater, 1);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Equal, 1);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Invalid, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn string_with_escaped_quote() {
+        let mut lexer = Lexer::new(r#""say \"hi\"\\" 'it\'s'"#);
+        assert_lexer_lexeme!(lexer, Kind::String, 14);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::String, 7);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let mut lexer = Lexer::new("\"abc\ncho 'x\\");
+        assert_lexer_lexeme!(lexer, Kind::UnterminatedString, 4);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 3);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::UnterminatedString, 3);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn fstring() {
+        let mut lexer = Lexer::new("f\"a {x} {{b}} {(1, 2)}\"");
+        assert_lexer_lexeme!(lexer, Kind::FStringStart, 2);
+        assert_lexer_lexeme!(lexer, Kind::FStringText, 2);
+        assert_lexer_lexeme!(lexer, Kind::LeftBrace, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::RightBrace, 1);
+        assert_lexer_lexeme!(lexer, Kind::FStringText, 7);
+        assert_lexer_lexeme!(lexer, Kind::LeftBrace, 1);
+        assert_lexer_lexeme!(lexer, Kind::LeftParen, 1);
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 1);
+        assert_lexer_lexeme!(lexer, Kind::Comma, 1);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 1);
+        assert_lexer_lexeme!(lexer, Kind::RightParen, 1);
+        assert_lexer_lexeme!(lexer, Kind::RightBrace, 1);
+        assert_lexer_lexeme!(lexer, Kind::FStringEnd, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn unterminated_fstring() {
+        let mut lexer = Lexer::new("f'ab\nc");
+        assert_lexer_lexeme!(lexer, Kind::FStringStart, 2);
+        assert_lexer_lexeme!(lexer, Kind::FStringText, 2);
+        assert_lexer_lexeme!(lexer, Kind::UnterminatedString, 0);
+        assert_lexer_lexeme!(lexer, Kind::Eol, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn triple_quoted_string() {
+        let mut lexer = Lexer::new("\"\"\"a\n\"b\"\n\"\"\" d'''x\n  y'''");
+        assert_lexer_lexeme!(lexer, Kind::String, 12);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::String, 12);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn empty_strings_are_not_triple_quoted() {
+        let mut lexer = Lexer::new("\"\" ''");
+        assert_lexer_lexeme!(lexer, Kind::String, 2);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::String, 2);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn unterminated_triple_quoted_string() {
+        let mut lexer = Lexer::new("\"\"\"ab\nc\"\"");
+        assert_lexer_lexeme!(lexer, Kind::UnterminatedString, 9);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn triple_quoted_fstring() {
+        let mut lexer = Lexer::new("df\"\"\"\n{x}\n\"\"\"");
+        assert_lexer_lexeme!(lexer, Kind::FStringStart, 5);
+        assert_lexer_lexeme!(lexer, Kind::FStringText, 1);
+        assert_lexer_lexeme!(lexer, Kind::LeftBrace, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::RightBrace, 1);
+        assert_lexer_lexeme!(lexer, Kind::FStringText, 1);
+        assert_lexer_lexeme!(lexer, Kind::FStringEnd, 3);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn arithmetic_operators() {
+        let mut lexer = Lexer::new("a**b//c%d*e/f");
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::DoubleAsterisk, 2);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::DoubleSlash, 2);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Percent, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Asterisk, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Slash, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn radix_prefixed_numbers() {
+        let mut lexer = Lexer::new("0xff_FF 0b10 0o7 0b12");
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 7);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 4);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 3);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 4);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn separated_and_scientific_numbers() {
+        let mut lexer = Lexer::new("1_000 1.5e-3 2E10 3.0 4e x");
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 5);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Float, 6);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Float, 4);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Float, 3);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Float, 2);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn letters_after_numbers_stay_in_the_number() {
+        let mut lexer = Lexer::new("123abc 1.5x 1e+ 2_0ố");
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 6);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Float, 4);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Float, 2);
+        assert_lexer_lexeme!(lexer, Kind::Plus, 1);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 6);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn dictionary_literal() {
+        let mut lexer = Lexer::new("{a: [1]}");
+        assert_lexer_lexeme!(lexer, Kind::LeftBrace, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 1);
+        assert_lexer_lexeme!(lexer, Kind::Colon, 1);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::LeftBracket, 1);
+        assert_lexer_lexeme!(lexer, Kind::Decimal, 1);
+        assert_lexer_lexeme!(lexer, Kind::RightBracket, 1);
+        assert_lexer_lexeme!(lexer, Kind::RightBrace, 1);
+        assert_lexer_eof!(lexer);
+    }
+
+    #[test]
+    fn member_access_on_multi_word_name() {
+        let mut lexer = Lexer::new("số lượng.làm tròn()");
+        assert_lexer_lexeme!(lexer, Kind::Word, 4);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 8);
+        assert_lexer_lexeme!(lexer, Kind::Period, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 4);
+        assert_lexer_lexeme!(lexer, Kind::WordSpaces, 1);
+        assert_lexer_lexeme!(lexer, Kind::Word, 5);
+        assert_lexer_lexeme!(lexer, Kind::LeftParen, 1);
+        assert_lexer_lexeme!(lexer, Kind::RightParen, 1);
+        assert_lexer_eof!(lexer);
+    }
 }
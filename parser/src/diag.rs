@@ -29,13 +29,31 @@ pub enum Error {
     BracketNotClosed {
         kind: BracketKind,
     },
+    InconsistentIndentation,
+    UnalignedDedent,
+    OutsideLoop {
+        keyword: String,
+    },
+    OutsideFunction {
+        keyword: String,
+    },
+    OutsideMethod {
+        keyword: String,
+    },
+    UnterminatedString,
+    InvalidEscape {
+        escape: String,
+    },
+    MalformedLiteral {
+        reason: String,
+    },
 }
 
 impl DiagData {
-    fn print_vec<T: Display + Sized>(vec: &Vec<T>) -> String {
+    fn print_vec<T: Display + Sized>(vec: &[T]) -> String {
         let mut result = String::new();
         for i in 0..vec.len() {
-            result.push_str(&format!("{}", vec[i]).as_str());
+            result.push_str(format!("{}", vec[i]).as_str());
             if i < vec.len() - 1 {
                 result.push_str(", ");
             }
@@ -62,6 +80,33 @@ impl Display for DiagData {
                 Error::BracketNotClosed { kind } => {
                     write!(f, "This {} has not been closed.", kind)
                 }
+                Error::InconsistentIndentation => {
+                    write!(f, "Inconsistent use of tabs and spaces in indentation")
+                }
+                Error::UnalignedDedent => {
+                    write!(f, "Dedentation does not match any outer indentation level")
+                }
+                Error::OutsideLoop { keyword } => {
+                    write!(f, "'{}' can only be used inside a loop", keyword)
+                }
+                Error::OutsideFunction { keyword } => {
+                    write!(f, "'{}' can only be used inside a function", keyword)
+                }
+                Error::OutsideMethod { keyword } => {
+                    write!(f, "'{}' can only be used inside a method", keyword)
+                }
+                Error::UnterminatedString => {
+                    write!(
+                        f,
+                        "This string has not been closed; only a triple-quoted string may span several lines"
+                    )
+                }
+                Error::InvalidEscape { escape } => {
+                    write!(f, "Invalid escape sequence '{}'", escape)
+                }
+                Error::MalformedLiteral { reason } => {
+                    write!(f, "Malformed literal: {}", reason)
+                }
             },
         }
     }
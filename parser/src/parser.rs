@@ -3,7 +3,11 @@ use lexer::{
     lexer::Lexer,
 };
 
-use crate::{Span, diag::Diag, syntax::Programme};
+use crate::{
+    Span,
+    diag::{Diag, DiagData, Error},
+    syntax::Programme,
+};
 
 pub struct Parser<'a> {
     input: &'a str,
@@ -11,6 +15,12 @@ pub struct Parser<'a> {
     pub cur_pos: u32,
     pub cur_lexeme: Lexeme,
     pub cur_line: u32,
+    /// Number of loops enclosing the statement being parsed.
+    pub loop_depth: u32,
+    /// Number of function definitions enclosing the statement being parsed.
+    pub function_depth: u32,
+    /// Number of method definitions enclosing the statement being parsed.
+    pub method_depth: u32,
     pub diag: Vec<Diag>,
 }
 
@@ -25,6 +35,9 @@ impl<'a> Parser<'a> {
                 len: 0,
             },
             cur_line: 1,
+            loop_depth: 0,
+            function_depth: 0,
+            method_depth: 0,
             diag: Vec::new(),
         };
         parser.cur_lexeme = parser.lexer.next();
@@ -32,6 +45,13 @@ impl<'a> Parser<'a> {
     }
 
     pub fn next_lexeme(&mut self) -> &Lexeme {
+        if matches!(
+            self.cur_lexeme.kind,
+            lexeme::Kind::String | lexeme::Kind::UnterminatedString | lexeme::Kind::FStringText
+        ) {
+            let newlines = self.cur_lexeme_snippet().matches('\n').count();
+            self.cur_line += newlines as u32;
+        }
         self.cur_pos += self.cur_lexeme.len;
         self.cur_lexeme = self.lexer.next();
         if matches!(self.cur_lexeme.kind, lexeme::Kind::Eol) {
@@ -42,10 +62,12 @@ impl<'a> Parser<'a> {
 
     pub fn skip_ws_if_any(&mut self, including_eol: bool) -> &Lexeme {
         match self.cur_lexeme.kind {
-            lexeme::Kind::Whitespaces | lexeme::Kind::WordSpaces => {
+            lexeme::Kind::Whitespaces | lexeme::Kind::WordSpaces | lexeme::Kind::Comment => {
+                self.next_non_ws_lexeme(including_eol)
+            }
+            lexeme::Kind::Eol if including_eol || self.lexer.in_brackets() => {
                 self.next_non_ws_lexeme(including_eol)
             }
-            lexeme::Kind::Eol if including_eol => self.next_non_ws_lexeme(including_eol),
             _ => &self.cur_lexeme,
         }
     }
@@ -54,8 +76,8 @@ impl<'a> Parser<'a> {
         loop {
             self.next_lexeme();
             match self.cur_lexeme.kind {
-                lexeme::Kind::Whitespaces | lexeme::Kind::WordSpaces => {}
-                lexeme::Kind::Eol if including_eol => {}
+                lexeme::Kind::Whitespaces | lexeme::Kind::WordSpaces | lexeme::Kind::Comment => {}
+                lexeme::Kind::Eol if including_eol || self.lexer.in_brackets() => {}
                 _ => return &self.cur_lexeme,
             }
         }
@@ -74,10 +96,47 @@ impl<'a> Parser<'a> {
         self.cur_lexeme_snippet() == expected
     }
 
+    /// Checks whether the words starting at the current lexeme spell out
+    /// `phrase`, whose words are separated by single spaces.
+    pub fn cur_phrase_is(&self, phrase: &str) -> bool {
+        if !matches!(self.cur_lexeme.kind, lexeme::Kind::Word) {
+            return false;
+        }
+        let mut rest = &self.input[self.cur_pos as usize..];
+        for (i, word) in phrase.split(' ').enumerate() {
+            if i > 0 {
+                let trimmed = rest.trim_start_matches(' ');
+                if trimmed.len() == rest.len() {
+                    return false;
+                }
+                rest = trimmed;
+            }
+            let Some(after) = rest.strip_prefix(word) else {
+                return false;
+            };
+            rest = after;
+        }
+        !rest.starts_with(Lexer::check_is_word_char)
+    }
+
     pub fn cur_span(&self) -> (u32, u32) {
         (self.cur_pos, self.cur_lexeme.len)
     }
 
+    /// Reports the current lexeme as unexpected, singling out the lexemes
+    /// that signal a broken indentation.
+    pub fn unexpected_lexeme(&self, expected: Vec<lexeme::Kind>) -> Diag {
+        Diag {
+            line: self.cur_line,
+            span: self.cur_span(),
+            data: DiagData::Err(match self.cur_lexeme.kind {
+                lexeme::Kind::MixedIndent => Error::InconsistentIndentation,
+                lexeme::Kind::UnalignedDedent => Error::UnalignedDedent,
+                found => Error::UnexpectedLexeme { expected, found },
+            }),
+        }
+    }
+
     pub fn visit_programme(&mut self) -> Option<Programme> {
         let status = Programme::accept(self);
         match status {
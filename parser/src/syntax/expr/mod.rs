@@ -1,22 +1,169 @@
+use std::{iter, rc::Rc};
+
 use lexer::lexeme;
 
 use crate::{
     diag::{BracketKind, Diag, DiagData, Error},
     parser::Parser,
-    syntax::expr::terminal::Literal,
+    syntax::expr::terminal::{Ident, Keyword, Literal, dedent, split_string_prefix, unescape},
 };
 
 pub mod terminal;
 
+/// An expression, with the line it starts on.
 #[derive(Debug)]
-pub struct Expr(pub AddAffixedExpr);
+pub struct Expr {
+    pub line: u32,
+    pub or: OrExpr,
+}
 
 impl Expr {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
-        Ok(match AddAffixedExpr::accept(parser)? {
-            Some(add_expr) => Some(Expr(add_expr)),
-            None => None,
-        })
+        let line = parser.cur_line;
+        Ok(OrExpr::accept(parser)?.map(|or| Expr { line, or }))
+    }
+}
+
+/// Accepts operands of `Operand` separated by the word operator `kw`.
+fn accept_word_separated<Operand>(
+    parser: &mut Parser,
+    kw: Keyword,
+    accept_operand: fn(&mut Parser) -> Result<Option<Operand>, Diag>,
+) -> Result<Option<Vec<Operand>>, Diag> {
+    let Some(first) = accept_operand(parser)? else {
+        return Ok(None);
+    };
+    let mut operands = vec![first];
+    loop {
+        parser.skip_ws_if_any(false);
+        let line = parser.cur_line;
+        let span = parser.cur_span();
+        if Keyword::accept(parser, kw)?.is_none() {
+            break;
+        }
+        let Some(operand) = accept_operand(parser)? else {
+            return Err(Diag {
+                line,
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: "an expression after operator".to_string(),
+                }),
+                span,
+            });
+        };
+        operands.push(operand);
+    }
+    Ok(Some(operands))
+}
+
+/// Operands joined by 'hoặc', evaluated until one of them is truthy.
+#[derive(Debug)]
+pub struct OrExpr(pub Vec<AndExpr>);
+
+impl OrExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        Ok(accept_word_separated(parser, Keyword::Hoac, AndExpr::accept)?.map(OrExpr))
+    }
+}
+
+/// Operands joined by 'và', evaluated until one of them is falsy.
+#[derive(Debug)]
+pub struct AndExpr(pub Vec<NotExpr>);
+
+impl AndExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        Ok(accept_word_separated(parser, Keyword::Va, NotExpr::accept)?.map(AndExpr))
+    }
+}
+
+#[derive(Debug)]
+pub enum NotExpr {
+    Not(Box<NotExpr>),
+    Comparison(ComparisonExpr),
+}
+
+impl NotExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        // 'không thì' opens an else branch rather than negating 'thì'.
+        if parser.cur_phrase_is(Keyword::KhongThi.as_str()) {
+            return Ok(None);
+        }
+        if Keyword::accept(parser, Keyword::Khong)?.is_none() {
+            return Ok(ComparisonExpr::accept(parser)?.map(NotExpr::Comparison));
+        }
+        match NotExpr::accept(parser)? {
+            Some(operand) => Ok(Some(NotExpr::Not(Box::new(operand)))),
+            None => Err(Diag {
+                line: parser.cur_line,
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!("an expression after '{}'", Keyword::Khong.as_str()),
+                }),
+                span: parser.cur_span(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ComparisonKind {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    In,
+}
+
+/// A chain of comparisons such as `0 < x <= 10`, which holds when every
+/// adjacent pair compares as stated. `x trong xs` tests whether `x` is a
+/// member of `xs`. Each comparison holds the line of its operator.
+#[derive(Debug)]
+pub struct ComparisonExpr {
+    pub first: AddAffixedExpr,
+    pub rest: Vec<(ComparisonKind, u32, AddAffixedExpr)>,
+}
+
+impl ComparisonExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let Some(first) = AddAffixedExpr::accept(parser)? else {
+            return Ok(None);
+        };
+        let mut rest = Vec::new();
+        loop {
+            parser.skip_ws_if_any(false);
+            let kind = match parser.cur_lexeme.kind {
+                lexeme::Kind::EqualEqual => ComparisonKind::Equal,
+                lexeme::Kind::BangEqual => ComparisonKind::NotEqual,
+                lexeme::Kind::Less => ComparisonKind::Less,
+                lexeme::Kind::LessEqual => ComparisonKind::LessEqual,
+                lexeme::Kind::Greater => ComparisonKind::Greater,
+                lexeme::Kind::GreaterEqual => ComparisonKind::GreaterEqual,
+                lexeme::Kind::Word if parser.cur_phrase_is(Keyword::Trong.as_str()) => {
+                    ComparisonKind::In
+                }
+                _ => break,
+            };
+            let line = parser.cur_line;
+            match kind {
+                ComparisonKind::In => {
+                    Keyword::accept(parser, Keyword::Trong)?;
+                }
+                _ => {
+                    parser.next_non_ws_lexeme(false); // consume op
+                }
+            }
+            let Some(rhs) = AddAffixedExpr::accept(parser)? else {
+                return Err(Diag {
+                    line: parser.cur_line,
+                    data: DiagData::Err(Error::MiscExpecting {
+                        expected: "an expression after operator".to_string(),
+                    }),
+                    span: parser.cur_span(),
+                });
+            };
+            rest.push((kind, line, rhs));
+        }
+        Ok(Some(ComparisonExpr { first, rest }))
     }
 }
 
@@ -24,11 +171,14 @@ impl Expr {
 pub struct AddAffixedExpr {
     pub lhs: Option<Box<AddAffixedExpr>>,
     pub rhs: (bool, MulAffixedExpr),
+    /// The line of the operator, if there is one.
+    pub line: u32,
 }
 
 impl AddAffixedExpr {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
         let mut lhs = None;
+        let mut line = parser.cur_line;
         let mut rhs = (
             true,
             match MulAffixedExpr::accept(parser)? {
@@ -37,14 +187,16 @@ impl AddAffixedExpr {
             },
         );
         loop {
-            parser.skip_ws_if_any(true);
+            parser.skip_ws_if_any(false);
             let op = match parser.cur_lexeme.kind {
                 lexeme::Kind::Plus => true,
                 lexeme::Kind::Minus => false,
                 _ => break,
             };
-            parser.next_non_ws_lexeme(true); // consume op
-            lhs = Some(Box::new(AddAffixedExpr { lhs, rhs }));
+            let op_line = parser.cur_line;
+            parser.next_non_ws_lexeme(false); // consume op
+            lhs = Some(Box::new(AddAffixedExpr { lhs, rhs, line }));
+            line = op_line;
             rhs = (
                 op,
                 match MulAffixedExpr::accept(parser)? {
@@ -61,35 +213,50 @@ impl AddAffixedExpr {
                 },
             );
         }
-        Ok(Some(AddAffixedExpr { lhs, rhs }))
+        Ok(Some(AddAffixedExpr { lhs, rhs, line }))
     }
 }
 
+#[derive(Debug)]
+pub enum MulKind {
+    Multiply,
+    Divide,
+    FloorDivide,
+    Modulo,
+}
+
 #[derive(Debug)]
 pub struct MulAffixedExpr {
     pub lhs: Option<Box<MulAffixedExpr>>,
-    pub rhs: (bool, PrefixedExpr),
+    pub rhs: (MulKind, PrefixedExpr),
+    /// The line of the operator, if there is one.
+    pub line: u32,
 }
 
 impl MulAffixedExpr {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
         let mut lhs = None;
+        let mut line = parser.cur_line;
         let mut rhs = (
-            true,
+            MulKind::Multiply,
             match PrefixedExpr::accept(parser)? {
                 Some(expr) => expr,
                 None => return Ok(None),
             },
         );
         loop {
-            parser.skip_ws_if_any(true);
+            parser.skip_ws_if_any(false);
             let op = match parser.cur_lexeme.kind {
-                lexeme::Kind::Asterisk => true,
-                lexeme::Kind::Slash => false,
+                lexeme::Kind::Asterisk => MulKind::Multiply,
+                lexeme::Kind::Slash => MulKind::Divide,
+                lexeme::Kind::DoubleSlash => MulKind::FloorDivide,
+                lexeme::Kind::Percent => MulKind::Modulo,
                 _ => break,
             };
-            parser.next_non_ws_lexeme(true); // consume op
-            lhs = Some(Box::new(MulAffixedExpr { lhs, rhs }));
+            let op_line = parser.cur_line;
+            parser.next_non_ws_lexeme(false); // consume op
+            lhs = Some(Box::new(MulAffixedExpr { lhs, rhs, line }));
+            line = op_line;
             rhs = (
                 op,
                 match PrefixedExpr::accept(parser)? {
@@ -106,7 +273,7 @@ impl MulAffixedExpr {
                 },
             );
         }
-        Ok(Some(MulAffixedExpr { lhs, rhs }))
+        Ok(Some(MulAffixedExpr { lhs, rhs, line }))
     }
 }
 
@@ -117,20 +284,28 @@ pub enum PrefixedExprKind {
 #[derive(Debug)]
 pub struct PrefixedExpr {
     pub prefix: Option<PrefixedExprKind>,
-    pub terminal: TerminalExpr,
+    pub power: PowerExpr,
+    /// The line of the prefix, if there is one.
+    pub line: u32,
 }
 
 impl PrefixedExpr {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let line = parser.cur_line;
         let prefix = match parser.cur_lexeme.kind {
             lexeme::Kind::Minus => {
-                parser.next_non_ws_lexeme(true);
+                parser.next_non_ws_lexeme(false);
                 Some(PrefixedExprKind::Minus)
             }
             _ => None,
         };
-        match TerminalExpr::accept(parser)? {
-            Some(terminal) => Ok(Some(PrefixedExpr { prefix, terminal })),
+        match PowerExpr::accept(parser)? {
+            Some(power) => Ok(Some(PrefixedExpr {
+                prefix,
+                power,
+                line,
+            })),
+            None if prefix.is_none() => Ok(None),
             None => Err(Diag {
                 line: parser.cur_line,
                 data: DiagData::Err(Error::MiscExpecting {
@@ -142,49 +317,396 @@ impl PrefixedExpr {
     }
 }
 
+/// A base raised to an exponent with `**`, which binds tighter than a
+/// prefix on its left and groups to the right, so `-2 ** 3 ** 2` reads as
+/// `-(2 ** (3 ** 2))`.
+#[derive(Debug)]
+pub struct PowerExpr {
+    pub base: PostfixedExpr,
+    pub exponent: Option<Box<PrefixedExpr>>,
+    /// The line of the operator, if there is one.
+    pub line: u32,
+}
+
+impl PowerExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let Some(base) = PostfixedExpr::accept(parser)? else {
+            return Ok(None);
+        };
+        parser.skip_ws_if_any(false);
+        let line = parser.cur_line;
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::DoubleAsterisk) {
+            return Ok(Some(PowerExpr {
+                base,
+                exponent: None,
+                line,
+            }));
+        }
+        parser.next_non_ws_lexeme(false); // consume op
+        match PrefixedExpr::accept(parser)? {
+            Some(exponent) => Ok(Some(PowerExpr {
+                base,
+                exponent: Some(Box::new(exponent)),
+                line,
+            })),
+            None => Err(Diag {
+                line: parser.cur_line,
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: "an expression after operator".to_string(),
+                }),
+                span: parser.cur_span(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Postfix {
+    Call(TupleExpr),
+    Subscript(Subscript),
+    /// A member looked up with a period, e.g. `.làm tròn` in
+    /// `số lượng.làm tròn()`.
+    Member(Ident),
+}
+
+/// What follows a container in square brackets: an index such as `a[i]`,
+/// or a slice such as `a[i:j:k]` whose bounds may each be omitted.
+#[derive(Debug)]
+pub enum Subscript {
+    Index(Expr),
+    Slice {
+        start: Option<Expr>,
+        stop: Option<Expr>,
+        step: Option<Expr>,
+    },
+}
+
+impl Subscript {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::LeftBracket) {
+            return Ok(None);
+        }
+        let bracket_pos = parser.cur_pos;
+        parser.next_non_ws_lexeme(true);
+        let start = Expr::accept(parser)?;
+        parser.skip_ws_if_any(true);
+        let subscript = if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Colon) {
+            let Some(index) = start else {
+                return Err(Diag {
+                    line: parser.cur_line,
+                    data: DiagData::Err(Error::MiscExpecting {
+                        expected: "an index or a slice inside the brackets".to_string(),
+                    }),
+                    span: parser.cur_span(),
+                });
+            };
+            Subscript::Index(index)
+        } else {
+            parser.next_non_ws_lexeme(true);
+            let stop = Expr::accept(parser)?;
+            parser.skip_ws_if_any(true);
+            let mut step = None;
+            if matches!(parser.cur_lexeme.kind, lexeme::Kind::Colon) {
+                parser.next_non_ws_lexeme(true);
+                step = Expr::accept(parser)?;
+                parser.skip_ws_if_any(true);
+            }
+            Subscript::Slice { start, stop, step }
+        };
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::RightBracket) {
+            return Err(Diag {
+                line: parser.cur_line,
+                data: DiagData::Err(Error::BracketNotClosed {
+                    kind: BracketKind::Bracket,
+                }),
+                span: (bracket_pos, 1),
+            });
+        }
+        parser.next_lexeme();
+        Ok(Some(subscript))
+    }
+}
+
+/// A terminal followed by any number of postfixes, applied left to right,
+/// e.g. `f(1)(2)`, `a[0][1:]` or `chuỗi.viết hoa()`. A call applies to the
+/// whole identifier before it, so `bình phương(3)` calls `bình phương`.
+/// Each postfix holds the line it starts on.
+#[derive(Debug)]
+pub struct PostfixedExpr {
+    pub terminal: TerminalExpr,
+    pub postfixes: Vec<(u32, Postfix)>,
+}
+
+impl PostfixedExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let Some(terminal) = TerminalExpr::accept(parser)? else {
+            return Ok(None);
+        };
+        let mut postfixes = Vec::new();
+        loop {
+            parser.skip_ws_if_any(false);
+            let line = parser.cur_line;
+            if let Some(args) = TupleExpr::accept(parser)? {
+                postfixes.push((line, Postfix::Call(args)));
+            } else if let Some(subscript) = Subscript::accept(parser)? {
+                postfixes.push((line, Postfix::Subscript(subscript)));
+            } else if matches!(parser.cur_lexeme.kind, lexeme::Kind::Period) {
+                parser.next_non_ws_lexeme(false);
+                let Some(member) = Ident::accept(parser)? else {
+                    return Err(Diag {
+                        line: parser.cur_line,
+                        data: DiagData::Err(Error::MiscExpecting {
+                            expected: "a member name after '.'".to_string(),
+                        }),
+                        span: parser.cur_span(),
+                    });
+                };
+                postfixes.push((line, Postfix::Member(member)));
+            } else {
+                break;
+            }
+        }
+        Ok(Some(PostfixedExpr {
+            terminal,
+            postfixes,
+        }))
+    }
+
+    pub fn ends_with_call(&self) -> bool {
+        matches!(self.postfixes.last(), Some((_, Postfix::Call(_))))
+    }
+}
+
 #[derive(Debug)]
 pub enum TerminalExpr {
     Literal(Literal),
     Tuple(TupleExpr),
+    List(ListExpr),
+    Dict(DictExpr),
+    FString(FStringExpr),
+}
+
+/// Accepts expressions separated by commas, up to the closing bracket of
+/// `kind`, once the opening one has been consumed.
+fn accept_elements(parser: &mut Parser, kind: BracketKind) -> Result<Vec<Expr>, Diag> {
+    let mut exprs = Vec::new();
+    while let Some(expr) = Expr::accept(parser)? {
+        exprs.push(expr);
+        parser.skip_ws_if_any(true);
+        if matches!(parser.cur_lexeme.kind, lexeme::Kind::Comma) {
+            parser.next_non_ws_lexeme(true);
+            continue;
+        } else {
+            break;
+        }
+    }
+    let closed = match kind {
+        BracketKind::Parenthesis => matches!(parser.cur_lexeme.kind, lexeme::Kind::RightParen),
+        BracketKind::Brace => matches!(parser.cur_lexeme.kind, lexeme::Kind::RightBrace),
+        BracketKind::Bracket => matches!(parser.cur_lexeme.kind, lexeme::Kind::RightBracket),
+    };
+    if !closed {
+        return Err(Diag {
+            line: parser.cur_line,
+            data: DiagData::Err(Error::BracketNotClosed { kind }),
+            span: (parser.cur_pos, 1),
+        });
+    }
+    Ok(exprs)
+}
+
+/// A list literal such as `[1, 2, 3]`.
+#[derive(Debug)]
+pub struct ListExpr(pub Vec<Expr>);
+
+impl ListExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::LeftBracket) {
+            return Ok(None);
+        }
+        parser.next_non_ws_lexeme(true);
+        let exprs = accept_elements(parser, BracketKind::Bracket)?;
+        parser.next_lexeme();
+        Ok(Some(ListExpr(exprs)))
+    }
 }
 
 #[derive(Debug)]
 pub struct TupleExpr(pub Vec<Expr>);
 
+/// A dictionary literal such as `{"một": 1, "hai": 2}`.
+#[derive(Debug)]
+pub struct DictExpr(pub Vec<(Expr, Expr)>);
+
+impl DictExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::LeftBrace) {
+            return Ok(None);
+        }
+        parser.next_non_ws_lexeme(true);
+        let mut entries = Vec::new();
+        while let Some(key) = Expr::accept(parser)? {
+            parser.skip_ws_if_any(true);
+            if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Colon) {
+                return Err(parser.unexpected_lexeme(vec![lexeme::Kind::Colon]));
+            }
+            parser.next_non_ws_lexeme(true);
+            let Some(value) = Expr::accept(parser)? else {
+                return Err(Diag {
+                    line: parser.cur_line,
+                    data: DiagData::Err(Error::MiscExpecting {
+                        expected: "a value after the colon".to_string(),
+                    }),
+                    span: parser.cur_span(),
+                });
+            };
+            entries.push((key, value));
+            parser.skip_ws_if_any(true);
+            if matches!(parser.cur_lexeme.kind, lexeme::Kind::Comma) {
+                parser.next_non_ws_lexeme(true);
+            } else {
+                break;
+            }
+        }
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::RightBrace) {
+            return Err(Diag {
+                line: parser.cur_line,
+                data: DiagData::Err(Error::BracketNotClosed {
+                    kind: BracketKind::Brace,
+                }),
+                span: (parser.cur_pos, 1),
+            });
+        }
+        parser.next_lexeme();
+        Ok(Some(DictExpr(entries)))
+    }
+}
+
 impl TupleExpr {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
         Ok(match parser.cur_lexeme.kind {
             lexeme::Kind::LeftParen => {
                 parser.next_non_ws_lexeme(true);
-                let mut exprs = Vec::new();
-                loop {
+                let exprs = accept_elements(parser, BracketKind::Parenthesis)?;
+                parser.next_non_ws_lexeme(true);
+                Some(TupleExpr(exprs))
+            }
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum FStringSegment {
+    Text(Rc<str>),
+    Expr(Expr),
+}
+
+/// A formatted string such as `f"Xin chào {tên}"`, whose embedded
+/// expressions are formatted into the text around them.
+#[derive(Debug)]
+pub struct FStringExpr(pub Vec<FStringSegment>);
+
+impl FStringExpr {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::FStringStart) {
+            return Ok(None);
+        }
+        let start = parser.cur_pos;
+        let opening = parser.cur_lexeme.len;
+        let line = parser.cur_line;
+        let (prefix, _) = split_string_prefix(parser.cur_lexeme_snippet());
+        let dedented = prefix.contains('d');
+        parser.next_lexeme();
+        // The raw text around the embedded expressions, with its position,
+        // so that it is only unescaped once it has been dedented.
+        let mut texts = vec![(parser.cur_pos, "")];
+        let mut exprs = Vec::new();
+        loop {
+            match parser.cur_lexeme.kind {
+                lexeme::Kind::FStringText => {
+                    let span = parser.cur_span();
+                    if let Some(text) = texts.last_mut() {
+                        *text = (span.0, parser.get_snippet(&span));
+                    }
+                    parser.next_lexeme();
+                }
+                lexeme::Kind::LeftBrace => {
+                    let brace_pos = parser.cur_pos;
+                    parser.next_non_ws_lexeme(true);
                     let Some(expr) = Expr::accept(parser)? else {
-                        break;
+                        return Err(Diag {
+                            line: parser.cur_line,
+                            data: DiagData::Err(Error::MiscExpecting {
+                                expected: "an expression inside the braces".to_string(),
+                            }),
+                            span: parser.cur_span(),
+                        });
                     };
-                    exprs.push(expr);
                     parser.skip_ws_if_any(true);
-                    if matches!(parser.cur_lexeme.kind, lexeme::Kind::Comma) {
-                        parser.next_non_ws_lexeme(true);
-                        continue;
-                    } else {
-                        break;
+                    if !matches!(parser.cur_lexeme.kind, lexeme::Kind::RightBrace) {
+                        return Err(Diag {
+                            line: parser.cur_line,
+                            data: DiagData::Err(Error::BracketNotClosed {
+                                kind: BracketKind::Brace,
+                            }),
+                            span: (brace_pos, 1),
+                        });
                     }
+                    exprs.push(expr);
+                    parser.next_lexeme();
+                    texts.push((parser.cur_pos, ""));
+                }
+                lexeme::Kind::FStringEnd => {
+                    let segments = build_segments(parser, texts, exprs, dedented)?;
+                    parser.next_lexeme();
+                    return Ok(Some(FStringExpr(segments)));
                 }
-                if !matches!(parser.cur_lexeme.kind, lexeme::Kind::RightParen) {
+                lexeme::Kind::UnterminatedString => {
                     return Err(Diag {
-                        line: parser.cur_line,
-                        data: DiagData::Err(Error::BracketNotClosed {
-                            kind: BracketKind::Parenthesis,
-                        }),
-                        span: (parser.cur_pos, 1),
+                        line,
+                        data: DiagData::Err(Error::UnterminatedString),
+                        span: (start, opening),
                     });
                 }
-                parser.next_non_ws_lexeme(true);
-                Some(TupleExpr(exprs))
+                _ => return Err(parser.unexpected_lexeme(vec![lexeme::Kind::FStringEnd])),
             }
-            _ => None,
-        })
+        }
+    }
+}
+
+/// Unescapes the text of a formatted string, dedented first if asked to,
+/// and interleaves it with the embedded expressions.
+fn build_segments(
+    parser: &Parser,
+    texts: Vec<(u32, &str)>,
+    exprs: Vec<Expr>,
+    dedented: bool,
+) -> Result<Vec<FStringSegment>, Diag> {
+    let raws: Vec<_> = texts.iter().map(|&(_, raw)| raw).collect();
+    let kept = match dedented {
+        true => dedent(&raws),
+        false => raws
+            .iter()
+            .map(|raw| iter::once(0..raw.len()).collect())
+            .collect(),
+    };
+    let mut exprs = exprs.into_iter();
+    let mut segments = Vec::new();
+    for ((pos, raw), kept) in texts.into_iter().zip(kept) {
+        let mut text = String::new();
+        for range in kept {
+            text.push_str(&unescape(parser, pos + range.start as u32, &raw[range])?);
+        }
+        if !text.is_empty() {
+            let text = text.replace("{{", "{").replace("}}", "}");
+            segments.push(FStringSegment::Text(text.into()));
+        }
+        if let Some(expr) = exprs.next() {
+            segments.push(FStringSegment::Expr(expr));
+        }
     }
+    Ok(segments)
 }
 
 impl TerminalExpr {
@@ -193,8 +715,12 @@ impl TerminalExpr {
             Some(TerminalExpr::Literal(lit))
         } else if let Some(tuple) = TupleExpr::accept(parser)? {
             Some(TerminalExpr::Tuple(tuple))
+        } else if let Some(list) = ListExpr::accept(parser)? {
+            Some(TerminalExpr::List(list))
+        } else if let Some(dict) = DictExpr::accept(parser)? {
+            Some(TerminalExpr::Dict(dict))
         } else {
-            None
+            FStringExpr::accept(parser)?.map(TerminalExpr::FString)
         })
     }
 }
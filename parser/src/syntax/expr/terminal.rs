@@ -1,45 +1,149 @@
+use std::{iter, ops::Range, rc::Rc};
+
 use lexer::lexeme::Kind;
 
-use crate::{Span, diag::Diag, parser::Parser};
+use crate::{
+    Span,
+    diag::{Diag, DiagData, Error},
+    parser::Parser,
+};
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum Keyword {
     Cho,
+    Neu,
+    Thi,
+    ConNeu,
+    KhongThi,
+    TrongKhi,
+    Dung,
+    TiepTuc,
+    VoiMoi,
+    Trong,
+    Ham,
+    TraVe,
+    True,
+    False,
+    Va,
+    Hoac,
+    Khong,
+    Lop,
+    KeThua,
+    BanThan,
+    Thu,
+    Bat,
+    CuoiCung,
+    Nem,
+    SoKhop,
+    TruongHop,
 }
 
 impl Keyword {
+    /// Every keyword, none of which may appear inside an identifier.
+    pub const ALL: &[Keyword] = &[
+        Keyword::Cho,
+        Keyword::Neu,
+        Keyword::Thi,
+        Keyword::ConNeu,
+        Keyword::KhongThi,
+        Keyword::TrongKhi,
+        Keyword::Dung,
+        Keyword::TiepTuc,
+        Keyword::VoiMoi,
+        Keyword::Trong,
+        Keyword::Ham,
+        Keyword::TraVe,
+        Keyword::True,
+        Keyword::False,
+        Keyword::Va,
+        Keyword::Hoac,
+        Keyword::Khong,
+        Keyword::Lop,
+        Keyword::KeThua,
+        Keyword::BanThan,
+        Keyword::Thu,
+        Keyword::Bat,
+        Keyword::CuoiCung,
+        Keyword::Nem,
+        Keyword::SoKhop,
+        Keyword::TruongHop,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
             Keyword::Cho => "cho",
+            Keyword::Neu => "nếu",
+            Keyword::Thi => "thì",
+            Keyword::ConNeu => "còn nếu",
+            Keyword::KhongThi => "không thì",
+            Keyword::TrongKhi => "trong khi",
+            Keyword::Dung => "dừng",
+            Keyword::TiepTuc => "tiếp tục",
+            Keyword::VoiMoi => "với mỗi",
+            Keyword::Trong => "trong",
+            Keyword::Ham => "hàm",
+            Keyword::TraVe => "trả về",
+            Keyword::True => "đúng",
+            Keyword::False => "sai",
+            Keyword::Va => "và",
+            Keyword::Hoac => "hoặc",
+            Keyword::Khong => "không",
+            Keyword::Lop => "lớp",
+            Keyword::KeThua => "kế thừa",
+            Keyword::BanThan => "bản thân",
+            Keyword::Thu => "thử",
+            Keyword::Bat => "bắt",
+            Keyword::CuoiCung => "cuối cùng",
+            Keyword::Nem => "ném",
+            Keyword::SoKhop => "so khớp",
+            Keyword::TruongHop => "trường hợp",
         }
     }
 
     pub fn accept(parser: &mut Parser, kw: Self) -> Result<Option<Self>, Diag> {
-        Ok(match parser.cur_lexeme.kind {
-            Kind::Word if parser.cur_lexeme_snippet_is(kw.as_str()) => {
-                parser.next_non_ws_lexeme(true);
-                Some(kw)
-            }
-            _ => None,
-        })
+        if !parser.cur_phrase_is(kw.as_str()) {
+            return Ok(None);
+        }
+        for _ in 1..kw.as_str().split(' ').count() {
+            parser.next_lexeme(); // word spaces
+            parser.next_lexeme(); // next word of the keyword
+        }
+        parser.next_non_ws_lexeme(false);
+        Ok(Some(kw))
+    }
+
+    pub fn is_reserved(parser: &Parser) -> bool {
+        Self::ALL.iter().any(|kw| parser.cur_phrase_is(kw.as_str()))
     }
 }
 
 #[derive(Debug)]
 pub struct Ident(pub Span);
+/// A float literal, holding its value rounded to the nearest float.
+#[derive(Debug)]
+pub struct Float(pub Span, pub f64);
+/// An integer literal, holding its radix and its digits without the radix
+/// prefix and separators.
 #[derive(Debug)]
-pub struct Float(pub Span);
+pub struct Decimal(pub Span, pub u32, pub Rc<str>);
+/// A single- or double-quoted string, holding its content with the quotes
+/// stripped and escape sequences replaced.
 #[derive(Debug)]
-pub struct Decimal(pub Span);
+pub struct QuotedString(pub Span, pub Rc<str>);
 #[derive(Debug)]
-pub struct DoubleQuotedString(pub Span);
+pub struct Boolean(pub bool);
+/// `bản thân`, the instance that the enclosing method was called on.
+#[derive(Debug)]
+pub struct SelfRef;
 
 #[derive(Debug)]
 pub enum Literal {
     Ident(Ident),
     Float(Float),
     Decimal(Decimal),
-    DoubleQuotedString(DoubleQuotedString),
+    QuotedString(QuotedString),
+    Boolean(Boolean),
+    SelfRef(SelfRef),
 }
 
 impl Literal {
@@ -50,10 +154,12 @@ impl Literal {
             Some(Literal::Float(float))
         } else if let Some(decimal) = Decimal::accept(parser)? {
             Some(Literal::Decimal(decimal))
-        } else if let Some(dqs) = DoubleQuotedString::accept(parser)? {
-            Some(Literal::DoubleQuotedString(dqs))
+        } else if let Some(qs) = QuotedString::accept(parser)? {
+            Some(Literal::QuotedString(qs))
+        } else if let Some(boolean) = Boolean::accept(parser)? {
+            Some(Literal::Boolean(boolean))
         } else {
-            None
+            SelfRef::accept(parser)?.map(Literal::SelfRef)
         })
     }
 }
@@ -61,7 +167,7 @@ impl Literal {
 impl Ident {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
         Ok(match parser.cur_lexeme.kind {
-            Kind::Word => {
+            Kind::Word if !Keyword::is_reserved(parser) => {
                 let start = parser.cur_pos;
                 let mut len = parser.cur_lexeme.len;
                 loop {
@@ -75,10 +181,10 @@ impl Ident {
                             break;
                         }
                     };
-                    let word = parser.next_lexeme();
-                    match word.kind {
-                        Kind::Word => {
-                            len += word.len + added;
+                    parser.next_lexeme();
+                    match parser.cur_lexeme.kind {
+                        Kind::Word if !Keyword::is_reserved(parser) => {
+                            len += parser.cur_lexeme.len + added;
                         }
                         _ => {
                             break;
@@ -97,8 +203,47 @@ impl Float {
         Ok(match parser.cur_lexeme.kind {
             Kind::Float => {
                 let span = parser.cur_span();
+                let lit = parser.get_snippet(&span);
+                let (mantissa, exponent) = match lit.find(['e', 'E']) {
+                    Some(i) => (&lit[..i], Some(i)),
+                    None => (lit, None),
+                };
+                let mut text = match mantissa.split_once('.') {
+                    Some((int, frac)) => {
+                        let frac_start = span.0 + int.len() as u32 + 1;
+                        format!(
+                            "{}.{}",
+                            strip_separators(parser, span.0, int, 10, false)?,
+                            strip_separators(parser, frac_start, frac, 10, false)?
+                        )
+                    }
+                    None => strip_separators(parser, span.0, mantissa, 10, false)?,
+                };
+                if let Some(i) = exponent {
+                    let exponent = &lit[i + 1..];
+                    let digits = exponent.trim_start_matches(['+', '-']);
+                    let sign = &exponent[..exponent.len() - digits.len()];
+                    let digits_start = span.0 + (lit.len() - digits.len()) as u32;
+                    if digits.is_empty() {
+                        return Err(malformed_literal(
+                            parser,
+                            span,
+                            format!("expecting digits after the exponent '{}'", &lit[i..]),
+                        ));
+                    }
+                    text.push('e');
+                    text.push_str(sign);
+                    text.push_str(&strip_separators(parser, digits_start, digits, 10, false)?);
+                }
+                let Ok(value) = text.parse() else {
+                    return Err(malformed_literal(
+                        parser,
+                        span,
+                        format!("'{}' is not a number", lit),
+                    ));
+                };
                 parser.next_lexeme();
-                Some(Self(span))
+                Some(Self(span, value))
             }
             _ => None,
         })
@@ -110,23 +255,261 @@ impl Decimal {
         Ok(match parser.cur_lexeme.kind {
             Kind::Decimal => {
                 let span = parser.cur_span();
+                let lit = parser.get_snippet(&span);
+                let (radix, prefix) = match lit.get(..2).map(str::to_ascii_lowercase).as_deref() {
+                    Some(prefix @ "0x") => (16, Some(prefix.to_string())),
+                    Some(prefix @ "0o") => (8, Some(prefix.to_string())),
+                    Some(prefix @ "0b") => (2, Some(prefix.to_string())),
+                    _ => (10, None),
+                };
+                let digits = match prefix {
+                    Some(prefix) if lit.len() == prefix.len() => {
+                        return Err(malformed_literal(
+                            parser,
+                            span,
+                            format!("expecting {} digits after '{}'", radix_name(radix), prefix),
+                        ));
+                    }
+                    Some(_) => strip_separators(parser, span.0 + 2, &lit[2..], radix, true)?,
+                    None => strip_separators(parser, span.0, lit, radix, false)?,
+                };
                 parser.next_lexeme();
-                Some(Self(span))
+                Some(Self(span, radix, digits.into()))
             }
             _ => None,
         })
     }
 }
 
-impl DoubleQuotedString {
+fn radix_name(radix: u32) -> &'static str {
+    match radix {
+        2 => "binary",
+        8 => "octal",
+        16 => "hexadecimal",
+        _ => "decimal",
+    }
+}
+
+fn malformed_literal(parser: &Parser, span: Span, reason: String) -> Diag {
+    Diag {
+        line: parser.cur_line,
+        span,
+        data: DiagData::Err(Error::MalformedLiteral { reason }),
+    }
+}
+
+/// Removes the '_' separators from the digits in `raw`, which starts at
+/// position `start` of the input. A separator must sit between two digits,
+/// or right after a radix prefix.
+fn strip_separators(
+    parser: &Parser,
+    start: u32,
+    raw: &str,
+    radix: u32,
+    after_prefix: bool,
+) -> Result<String, Diag> {
+    let mut digits = String::with_capacity(raw.len());
+    let mut after_digit = after_prefix;
+    let mut chars = raw.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let span = (start + i as u32, c.len_utf8() as u32);
+        if c == '_' {
+            let before_digit = chars.peek().is_some_and(|&(_, c)| c.is_digit(radix));
+            if !after_digit || !before_digit {
+                return Err(malformed_literal(
+                    parser,
+                    span,
+                    "a digit separator '_' must be between two digits".to_string(),
+                ));
+            }
+            after_digit = false;
+        } else if c.is_digit(radix) {
+            digits.push(c);
+            after_digit = true;
+        } else {
+            return Err(malformed_literal(
+                parser,
+                span,
+                format!("'{}' is not a {} digit", c, radix_name(radix)),
+            ));
+        }
+    }
+    Ok(digits)
+}
+
+impl Boolean {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        Ok(if Keyword::accept(parser, Keyword::True)?.is_some() {
+            Some(Self(true))
+        } else if Keyword::accept(parser, Keyword::False)?.is_some() {
+            Some(Self(false))
+        } else {
+            None
+        })
+    }
+}
+
+impl SelfRef {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let span = parser.cur_span();
+        let line = parser.cur_line;
+        let Some(kw) = Keyword::accept(parser, Keyword::BanThan)? else {
+            return Ok(None);
+        };
+        if parser.method_depth == 0 {
+            return Err(Diag {
+                line,
+                span,
+                data: DiagData::Err(Error::OutsideMethod {
+                    keyword: kw.as_str().to_string(),
+                }),
+            });
+        }
+        Ok(Some(SelfRef))
+    }
+}
+
+impl QuotedString {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
         Ok(match parser.cur_lexeme.kind {
             Kind::String => {
                 let span = parser.cur_span();
+                let lit = parser.get_snippet(&span);
+                let (prefix, quotes) = split_string_prefix(lit);
+                let start = prefix.len() + quotes;
+                let raw = &lit[start..lit.len() - quotes];
+                let kept = match prefix.contains('d') {
+                    true => dedent(&[raw]).remove(0),
+                    false => iter::once(0..raw.len()).collect(),
+                };
+                let mut content = String::new();
+                for range in kept {
+                    let pos = span.0 + (start + range.start) as u32;
+                    content.push_str(&unescape(parser, pos, &raw[range])?);
+                }
                 parser.next_lexeme();
-                Some(Self(span))
+                Some(Self(span, content.into()))
+            }
+            Kind::UnterminatedString => {
+                return Err(Diag {
+                    line: parser.cur_line,
+                    span: (parser.cur_pos, 1),
+                    data: DiagData::Err(Error::UnterminatedString),
+                });
             }
             _ => None,
         })
     }
 }
+
+/// Splits the opening of a string lexeme into its prefix letters and the
+/// number of quotes that open and close it.
+pub fn split_string_prefix(lit: &str) -> (&str, usize) {
+    let rest = lit.trim_start_matches(['f', 'd']);
+    let prefix = &lit[..lit.len() - rest.len()];
+    let quote = &rest[..1];
+    let quotes = if rest.len() >= 6 && rest.starts_with(&quote.repeat(3)) {
+        3
+    } else {
+        1
+    };
+    (prefix, quotes)
+}
+
+/// Finds what is left of a string's raw text once the indentation shared by
+/// every non-blank line is stripped, along with a line break right after the
+/// opening quotes, so that a string spanning several lines can be indented
+/// like the code around it. The text comes in pieces, split where a formatted
+/// string embeds an expression, which counts as part of its line. The byte
+/// ranges to keep are given for each piece.
+pub fn dedent(pieces: &[&str]) -> Vec<Vec<Range<usize>>> {
+    let last = pieces.len().saturating_sub(1);
+    let opening = |p: usize| usize::from(p == 0 && pieces[0].starts_with('\n'));
+    // Where each line starts, the whitespace before its content, and whether
+    // it has any content.
+    let mut lines = Vec::new();
+    for (p, piece) in pieces.iter().enumerate() {
+        let first = (p == 0 && opening(0) == 0).then_some(0);
+        for start in first
+            .into_iter()
+            .chain(piece.match_indices('\n').map(|(i, _)| i + 1))
+        {
+            let line = piece[start..].split('\n').next().unwrap_or_default();
+            let indent = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+            let ends_piece = start + line.len() == piece.len();
+            let is_blank = line.trim().is_empty() && (!ends_piece || p == last);
+            lines.push((p, start, line, indent, is_blank));
+        }
+    }
+    let indent = lines
+        .iter()
+        .filter(|(.., is_blank)| !is_blank)
+        .map(|&(.., indent, _)| indent)
+        .reduce(|common, indent| {
+            let shared = common
+                .char_indices()
+                .zip(indent.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(indent.len()), |((i, _), _)| i);
+            &common[..shared]
+        })
+        .unwrap_or("");
+    let mut kept: Vec<_> = (0..pieces.len()).map(|_| Vec::new()).collect();
+    let mut copied: Vec<_> = (0..pieces.len()).map(opening).collect();
+    for (p, start, line, _, is_blank) in lines {
+        kept[p].push(copied[p]..start);
+        copied[p] = start + if is_blank { line.len() } else { indent.len() };
+    }
+    for (p, piece) in pieces.iter().enumerate() {
+        kept[p].push(copied[p]..piece.len());
+        kept[p].retain(|range| !range.is_empty());
+    }
+    kept
+}
+
+/// Replaces the escape sequences in `raw`, which starts at position `start`
+/// of the input: `\n`, `\t`, `\r`, `\0`, `\\`, `\"`, `\'` and `\u{1EA1}`.
+pub fn unescape(parser: &Parser, start: u32, raw: &str) -> Result<String, Diag> {
+    let mut result = String::with_capacity(raw.len());
+    let mut chars = raw.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let escaped = match chars.next().map(|(_, c)| c) {
+            Some('n') => Some('\n'),
+            Some('t') => Some('\t'),
+            Some('r') => Some('\r'),
+            Some('0') => Some('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => Some(c),
+            Some('u') if chars.next_if(|&(_, c)| c == '{').is_some() => {
+                let mut digits = String::new();
+                while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_hexdigit()) {
+                    digits.push(c);
+                }
+                match chars.next_if(|&(_, c)| c == '}') {
+                    Some(_) if (1..=6).contains(&digits.len()) => u32::from_str_radix(&digits, 16)
+                        .ok()
+                        .and_then(char::from_u32),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        match escaped {
+            Some(c) => result.push(c),
+            None => {
+                let end = chars.peek().map_or(raw.len(), |&(j, _)| j);
+                return Err(Diag {
+                    line: parser.cur_line,
+                    span: (start + i as u32, (end - i) as u32),
+                    data: DiagData::Err(Error::InvalidEscape {
+                        escape: raw[i..end].to_string(),
+                    }),
+                });
+            }
+        }
+    }
+    Ok(result)
+}
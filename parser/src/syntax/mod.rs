@@ -1,6 +1,9 @@
+use lexer::lexeme;
+
 use crate::{diag::Diag, parser::Parser, syntax::stmt::Statement};
 
 pub mod expr;
+pub mod pattern;
 pub mod stmt;
 
 #[derive(Debug)]
@@ -11,13 +14,13 @@ pub struct Programme {
 impl Programme {
     pub fn accept(parser: &mut Parser) -> Result<Self, Diag> {
         let mut statements = Vec::new();
-        loop {
+        parser.skip_ws_if_any(true);
+        while let Some(stmt) = Statement::accept(parser)? {
+            statements.push(stmt);
             parser.skip_ws_if_any(true);
-            if let Some(stmt) = Statement::accept(parser)? {
-                statements.push(stmt);
-            } else {
-                break;
-            }
+        }
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Eof) {
+            return Err(parser.unexpected_lexeme(vec![lexeme::Kind::Eof]));
         }
         Ok(Programme { statements })
     }
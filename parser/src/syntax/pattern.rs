@@ -0,0 +1,150 @@
+use lexer::lexeme;
+
+use crate::{
+    diag::{BracketKind, Diag, DiagData, Error},
+    parser::Parser,
+    syntax::expr::terminal::{Boolean, Decimal, Float, Ident, Literal, QuotedString},
+};
+
+/// The shape a 'trường hợp' case expects the matched value to have.
+#[derive(Debug)]
+pub enum Pattern {
+    /// `_`, which matches anything without binding it.
+    Wildcard,
+    /// A name, which matches anything and binds it to the value.
+    Binding(Ident),
+    /// A number, string or boolean, optionally negated, which matches the
+    /// values equal to it.
+    Literal { negative: bool, lit: Literal },
+    /// `(a, b)`, which matches a tuple element by element.
+    Tuple(Vec<Pattern>),
+    /// `[a, b]`, which matches a list element by element.
+    List(Vec<Pattern>),
+    /// `*rest` inside a tuple or list pattern, which matches the elements
+    /// the others leave over, and binds them unless it is `*_`.
+    Rest(Option<Ident>),
+}
+
+impl Pattern {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        match parser.cur_lexeme.kind {
+            lexeme::Kind::LeftParen => {
+                let mut elements = Self::accept_elements(parser, BracketKind::Parenthesis)?;
+                Ok(Some(match elements.len() {
+                    1 if !matches!(elements[0], Pattern::Rest(_)) => elements.remove(0),
+                    _ => Pattern::Tuple(elements),
+                }))
+            }
+            lexeme::Kind::LeftBracket => Ok(Some(Pattern::List(Self::accept_elements(
+                parser,
+                BracketKind::Bracket,
+            )?))),
+            lexeme::Kind::Minus => {
+                parser.next_non_ws_lexeme(false);
+                let lit = if let Some(float) = Float::accept(parser)? {
+                    Literal::Float(float)
+                } else if let Some(decimal) = Decimal::accept(parser)? {
+                    Literal::Decimal(decimal)
+                } else {
+                    return Err(Diag {
+                        line: parser.cur_line,
+                        span: parser.cur_span(),
+                        data: DiagData::Err(Error::MiscExpecting {
+                            expected: "a number after '-' in the pattern".to_string(),
+                        }),
+                    });
+                };
+                Ok(Some(Pattern::Literal {
+                    negative: true,
+                    lit,
+                }))
+            }
+            _ => {
+                if let Some(ident) = Ident::accept(parser)? {
+                    return Ok(Some(Self::name(parser, ident)));
+                }
+                let lit = if let Some(float) = Float::accept(parser)? {
+                    Literal::Float(float)
+                } else if let Some(decimal) = Decimal::accept(parser)? {
+                    Literal::Decimal(decimal)
+                } else if let Some(qs) = QuotedString::accept(parser)? {
+                    Literal::QuotedString(qs)
+                } else if let Some(boolean) = Boolean::accept(parser)? {
+                    Literal::Boolean(boolean)
+                } else {
+                    return Ok(None);
+                };
+                Ok(Some(Pattern::Literal {
+                    negative: false,
+                    lit,
+                }))
+            }
+        }
+    }
+
+    fn name(parser: &Parser, ident: Ident) -> Self {
+        match parser.get_snippet(&ident.0) {
+            "_" => Pattern::Wildcard,
+            _ => Pattern::Binding(ident),
+        }
+    }
+
+    /// Accepts the comma-separated patterns inside a pair of brackets, of
+    /// which at most one may be a rest element.
+    fn accept_elements(parser: &mut Parser, kind: BracketKind) -> Result<Vec<Self>, Diag> {
+        parser.next_non_ws_lexeme(true);
+        let mut elements = Vec::new();
+        loop {
+            let element = if matches!(parser.cur_lexeme.kind, lexeme::Kind::Asterisk) {
+                let span = parser.cur_span();
+                parser.next_non_ws_lexeme(false);
+                let Some(ident) = Ident::accept(parser)? else {
+                    return Err(Diag {
+                        line: parser.cur_line,
+                        span: parser.cur_span(),
+                        data: DiagData::Err(Error::MiscExpecting {
+                            expected: "a name after '*' in the pattern".to_string(),
+                        }),
+                    });
+                };
+                if elements.iter().any(|p| matches!(p, Pattern::Rest(_))) {
+                    return Err(Diag {
+                        line: parser.cur_line,
+                        span,
+                        data: DiagData::Err(Error::MiscExpecting {
+                            expected: "at most one '*' element in the pattern".to_string(),
+                        }),
+                    });
+                }
+                match Self::name(parser, ident) {
+                    Pattern::Binding(ident) => Pattern::Rest(Some(ident)),
+                    _ => Pattern::Rest(None),
+                }
+            } else {
+                match Self::accept(parser)? {
+                    Some(pattern) => pattern,
+                    None => break,
+                }
+            };
+            elements.push(element);
+            parser.skip_ws_if_any(true);
+            if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Comma) {
+                break;
+            }
+            parser.next_non_ws_lexeme(true);
+        }
+        let closed = match kind {
+            BracketKind::Parenthesis => matches!(parser.cur_lexeme.kind, lexeme::Kind::RightParen),
+            _ => matches!(parser.cur_lexeme.kind, lexeme::Kind::RightBracket),
+        };
+        if !closed {
+            return Err(Diag {
+                line: parser.cur_line,
+                data: DiagData::Err(Error::BracketNotClosed { kind }),
+                span: (parser.cur_pos, 1),
+            });
+        }
+        parser.next_lexeme();
+        Ok(elements)
+    }
+}
@@ -1,32 +1,121 @@
+use std::rc::Rc;
+
 use lexer::lexeme;
 
 use crate::{
-    diag::{Diag, DiagData, Error},
+    diag::{BracketKind, Diag, DiagData, Error},
     parser::Parser,
     syntax::expr::{
-        Expr, TupleExpr,
-        terminal::{Ident, Keyword},
+        Expr, Postfix, PostfixedExpr, Subscript, TerminalExpr,
+        terminal::{Ident, Keyword, Literal},
     },
+    syntax::pattern::Pattern,
 };
 
+/// A statement, with the line it starts on.
+#[derive(Debug)]
+pub struct Statement {
+    pub line: u32,
+    pub kind: StatementKind,
+}
+
+impl Statement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let line = parser.cur_line;
+        Ok(StatementKind::accept(parser)?.map(|kind| Statement { line, kind }))
+    }
+}
+
 #[derive(Debug)]
-pub enum Statement {
+pub enum StatementKind {
     Cho(ChoStatement),
+    Neu(NeuStatement),
+    TrongKhi(TrongKhiStatement),
+    VoiMoi(VoiMoiStatement),
+    Dung(DungStatement),
+    TiepTuc(TiepTucStatement),
+    Ham(Rc<HamStatement>),
+    TraVe(TraVeStatement),
+    Lop(LopStatement),
+    Thu(ThuStatement),
+    Nem(NemStatement),
+    SoKhop(SoKhopStatement),
+    Assignment(AssignmentStatement),
     Invocation(InvocationStatement),
 }
 
-impl Statement {
+impl StatementKind {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
         if let Some(cho_stmt) = ChoStatement::accept(parser)? {
-            Ok(Some(Statement::Cho(cho_stmt)))
-        } else if let Some(invoc_stmt) = InvocationStatement::accept(parser)? {
-            Ok(Some(Statement::Invocation(invoc_stmt)))
+            Ok(Some(StatementKind::Cho(cho_stmt)))
+        } else if let Some(neu_stmt) = NeuStatement::accept(parser)? {
+            Ok(Some(StatementKind::Neu(neu_stmt)))
+        } else if let Some(trong_khi_stmt) = TrongKhiStatement::accept(parser)? {
+            Ok(Some(StatementKind::TrongKhi(trong_khi_stmt)))
+        } else if let Some(voi_moi_stmt) = VoiMoiStatement::accept(parser)? {
+            Ok(Some(StatementKind::VoiMoi(voi_moi_stmt)))
+        } else if let Some(dung_stmt) = DungStatement::accept(parser)? {
+            Ok(Some(StatementKind::Dung(dung_stmt)))
+        } else if let Some(tiep_tuc_stmt) = TiepTucStatement::accept(parser)? {
+            Ok(Some(StatementKind::TiepTuc(tiep_tuc_stmt)))
+        } else if let Some(ham_stmt) = HamStatement::accept(parser)? {
+            Ok(Some(StatementKind::Ham(Rc::new(ham_stmt))))
+        } else if let Some(tra_ve_stmt) = TraVeStatement::accept(parser)? {
+            Ok(Some(StatementKind::TraVe(tra_ve_stmt)))
+        } else if let Some(lop_stmt) = LopStatement::accept(parser)? {
+            Ok(Some(StatementKind::Lop(lop_stmt)))
+        } else if let Some(thu_stmt) = ThuStatement::accept(parser)? {
+            Ok(Some(StatementKind::Thu(thu_stmt)))
+        } else if let Some(nem_stmt) = NemStatement::accept(parser)? {
+            Ok(Some(StatementKind::Nem(nem_stmt)))
+        } else if let Some(so_khop_stmt) = SoKhopStatement::accept(parser)? {
+            Ok(Some(StatementKind::SoKhop(so_khop_stmt)))
+        } else if let Some(target) = PostfixedExpr::accept(parser)? {
+            parser.skip_ws_if_any(false);
+            Ok(Some(match AssignmentKind::accept(parser) {
+                Some(kind) => {
+                    StatementKind::Assignment(AssignmentStatement::accept(parser, target, kind)?)
+                }
+                None => StatementKind::Invocation(InvocationStatement::accept(parser, target)?),
+            }))
         } else {
             Ok(None)
         }
     }
 }
 
+/// An indented run of statements following a compound statement's header.
+#[derive(Debug)]
+pub struct Block {
+    pub statements: Vec<Statement>,
+}
+
+impl Block {
+    pub fn accept(parser: &mut Parser) -> Result<Self, Diag> {
+        parser.skip_ws_if_any(true); // the end of the header's line
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Indent) {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: "an indented block".to_string(),
+                }),
+            });
+        }
+        parser.next_non_ws_lexeme(true); // consume indent
+        let mut statements = Vec::new();
+        while let Some(stmt) = Statement::accept(parser)? {
+            statements.push(stmt);
+            parser.skip_ws_if_any(true);
+        }
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Dedent) {
+            return Err(parser.unexpected_lexeme(vec![lexeme::Kind::Dedent]));
+        }
+        parser.next_non_ws_lexeme(true); // consume dedent
+        Ok(Block { statements })
+    }
+}
+
 #[derive(Debug)]
 pub struct ChoStatement {
     pub kw: Keyword,
@@ -51,7 +140,7 @@ impl ChoStatement {
         parser.skip_ws_if_any(false);
         Ok(match parser.cur_lexeme.kind {
             lexeme::Kind::Equal => {
-                parser.next_non_ws_lexeme(true); // consume '='
+                parser.next_non_ws_lexeme(false); // consume '='
                 let Some(rhs) = Expr::accept(parser)? else {
                     return Err(Diag {
                         line: parser.cur_line,
@@ -73,25 +162,651 @@ impl ChoStatement {
 }
 
 #[derive(Debug)]
-pub struct InvocationStatement {
-    pub callee: Ident,
-    pub tuple: TupleExpr,
+pub struct NeuStatement {
+    /// Each condition with the block it guards, in order of appearance.
+    pub branches: Vec<(Expr, Block)>,
+    pub otherwise: Option<Block>,
 }
 
-impl InvocationStatement {
+impl NeuStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if Keyword::accept(parser, Keyword::Neu)?.is_none() {
+            return Ok(None);
+        }
+        let mut branches = vec![Self::accept_branch(parser, Keyword::Neu)?];
+        while Keyword::accept(parser, Keyword::ConNeu)?.is_some() {
+            branches.push(Self::accept_branch(parser, Keyword::ConNeu)?);
+        }
+        let otherwise = match Keyword::accept(parser, Keyword::KhongThi)? {
+            Some(_) => Some(Block::accept(parser)?),
+            None => None,
+        };
+        Ok(Some(NeuStatement {
+            branches,
+            otherwise,
+        }))
+    }
+
+    fn accept_branch(parser: &mut Parser, kw: Keyword) -> Result<(Expr, Block), Diag> {
+        let cond = accept_condition(parser, kw)?;
+        Ok((cond, Block::accept(parser)?))
+    }
+}
+
+/// Accepts the condition of a compound statement opened by `kw`, up to and
+/// including the closing 'thì'.
+fn accept_condition(parser: &mut Parser, kw: Keyword) -> Result<Expr, Diag> {
+    let Some(cond) = Expr::accept(parser)? else {
+        return Err(Diag {
+            line: parser.cur_line,
+            span: parser.cur_span(),
+            data: DiagData::Err(Error::MiscExpecting {
+                expected: format!("a condition after '{}'", kw.as_str()),
+            }),
+        });
+    };
+    expect_keyword(parser, Keyword::Thi, "the condition")?;
+    Ok(cond)
+}
+
+fn expect_keyword(parser: &mut Parser, kw: Keyword, after: &str) -> Result<Keyword, Diag> {
+    parser.skip_ws_if_any(false);
+    let expected = format!("'{}' after {}", kw.as_str(), after);
+    match Keyword::accept(parser, kw)? {
+        Some(kw) => Ok(kw),
+        None => Err(Diag {
+            line: parser.cur_line,
+            span: parser.cur_span(),
+            data: DiagData::Err(Error::MiscExpecting { expected }),
+        }),
+    }
+}
+
+#[derive(Debug)]
+pub struct TrongKhiStatement {
+    pub cond: Expr,
+    pub body: Block,
+}
+
+impl TrongKhiStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if Keyword::accept(parser, Keyword::TrongKhi)?.is_none() {
+            return Ok(None);
+        }
+        let cond = accept_condition(parser, Keyword::TrongKhi)?;
+        parser.loop_depth += 1;
+        let body = Block::accept(parser);
+        parser.loop_depth -= 1;
+        Ok(Some(TrongKhiStatement { cond, body: body? }))
+    }
+}
+
+#[derive(Debug)]
+pub struct VoiMoiStatement {
+    pub var: Ident,
+    pub iterable: Expr,
+    pub body: Block,
+}
+
+impl VoiMoiStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if Keyword::accept(parser, Keyword::VoiMoi)?.is_none() {
+            return Ok(None);
+        }
+        let Some(var) = Ident::accept(parser)? else {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!("a loop variable after '{}'", Keyword::VoiMoi.as_str()),
+                }),
+            });
+        };
+        expect_keyword(parser, Keyword::Trong, "the loop variable")?;
+        let Some(iterable) = Expr::accept(parser)? else {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!("an iterable after '{}'", Keyword::Trong.as_str()),
+                }),
+            });
+        };
+        expect_keyword(parser, Keyword::Thi, "the iterable")?;
+        parser.loop_depth += 1;
+        let body = Block::accept(parser);
+        parser.loop_depth -= 1;
+        Ok(Some(VoiMoiStatement {
+            var,
+            iterable,
+            body: body?,
+        }))
+    }
+}
+
+/// Checks that a loop-only keyword such as 'dừng' appears inside a loop.
+fn accept_loop_keyword(parser: &mut Parser, kw: Keyword) -> Result<Option<Keyword>, Diag> {
+    let span = parser.cur_span();
+    let line = parser.cur_line;
+    let Some(kw) = Keyword::accept(parser, kw)? else {
+        return Ok(None);
+    };
+    if parser.loop_depth == 0 {
+        return Err(Diag {
+            line,
+            span,
+            data: DiagData::Err(Error::OutsideLoop {
+                keyword: kw.as_str().to_string(),
+            }),
+        });
+    }
+    Ok(Some(kw))
+}
+
+#[derive(Debug)]
+pub struct DungStatement {
+    pub kw: Keyword,
+}
+
+impl DungStatement {
     pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
-        let Some(callee) = Ident::accept(parser)? else {
+        Ok(accept_loop_keyword(parser, Keyword::Dung)?.map(|kw| DungStatement { kw }))
+    }
+}
+
+#[derive(Debug)]
+pub struct TiepTucStatement {
+    pub kw: Keyword,
+}
+
+impl TiepTucStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        Ok(accept_loop_keyword(parser, Keyword::TiepTuc)?.map(|kw| TiepTucStatement { kw }))
+    }
+}
+
+#[derive(Debug)]
+pub struct HamStatement {
+    pub name: Ident,
+    pub params: Vec<Ident>,
+    pub body: Block,
+}
+
+impl HamStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if Keyword::accept(parser, Keyword::Ham)?.is_none() {
             return Ok(None);
+        }
+        let Some(name) = Ident::accept(parser)? else {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!("a function name after '{}'", Keyword::Ham.as_str()),
+                }),
+            });
         };
-        let Some(tuple) = TupleExpr::accept(parser)? else {
+        parser.skip_ws_if_any(false);
+        let params = Self::accept_params(parser)?;
+        let loop_depth = parser.loop_depth;
+        parser.loop_depth = 0;
+        parser.function_depth += 1;
+        let body = Block::accept(parser);
+        parser.function_depth -= 1;
+        parser.loop_depth = loop_depth;
+        Ok(Some(HamStatement {
+            name,
+            params,
+            body: body?,
+        }))
+    }
+
+    fn accept_params(parser: &mut Parser) -> Result<Vec<Ident>, Diag> {
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::LeftParen) {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: "a parameter list after the function name".to_string(),
+                }),
+            });
+        }
+        parser.next_non_ws_lexeme(true); // consume '('
+        let mut params = Vec::new();
+        while let Some(param) = Ident::accept(parser)? {
+            params.push(param);
+            parser.skip_ws_if_any(true);
+            if matches!(parser.cur_lexeme.kind, lexeme::Kind::Comma) {
+                parser.next_non_ws_lexeme(true);
+            } else {
+                break;
+            }
+        }
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::RightParen) {
             return Err(Diag {
                 line: parser.cur_line,
                 span: (parser.cur_pos, 1),
+                data: DiagData::Err(Error::BracketNotClosed {
+                    kind: BracketKind::Parenthesis,
+                }),
+            });
+        }
+        parser.next_non_ws_lexeme(false); // consume ')'
+        Ok(params)
+    }
+}
+
+/// Defines a class, optionally inheriting the methods of another with
+/// `kế thừa`, followed by an indented block of methods. The method named
+/// `khởi tạo` is the constructor.
+#[derive(Debug)]
+pub struct LopStatement {
+    pub name: Ident,
+    pub parent: Option<Ident>,
+    pub methods: Vec<Rc<HamStatement>>,
+}
+
+impl LopStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if Keyword::accept(parser, Keyword::Lop)?.is_none() {
+            return Ok(None);
+        }
+        let Some(name) = Ident::accept(parser)? else {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
                 data: DiagData::Err(Error::MiscExpecting {
-                    expected: "a tuple of arguments for invocation".to_string(),
+                    expected: format!("a class name after '{}'", Keyword::Lop.as_str()),
+                }),
+            });
+        };
+        parser.skip_ws_if_any(false);
+        let mut parent = None;
+        if Keyword::accept(parser, Keyword::KeThua)?.is_some() {
+            let Some(ident) = Ident::accept(parser)? else {
+                return Err(Diag {
+                    line: parser.cur_line,
+                    span: parser.cur_span(),
+                    data: DiagData::Err(Error::MiscExpecting {
+                        expected: format!("a class name after '{}'", Keyword::KeThua.as_str()),
+                    }),
+                });
+            };
+            parent = Some(ident);
+        }
+        parser.skip_ws_if_any(true);
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Indent) {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: "an indented block of methods".to_string(),
                 }),
             });
+        }
+        parser.next_non_ws_lexeme(true); // consume indent
+        let mut methods = Vec::new();
+        loop {
+            parser.method_depth += 1;
+            let method = HamStatement::accept(parser);
+            parser.method_depth -= 1;
+            let Some(method) = method? else {
+                break;
+            };
+            methods.push(Rc::new(method));
+            parser.skip_ws_if_any(true);
+        }
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Dedent) {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!(
+                        "a method starting with '{}' in the class body",
+                        Keyword::Ham.as_str()
+                    ),
+                }),
+            });
+        }
+        parser.next_non_ws_lexeme(true); // consume dedent
+        Ok(Some(LopStatement {
+            name,
+            parent,
+            methods,
+        }))
+    }
+}
+
+/// Runs a block, handling the exception it raises in the 'bắt' block, which
+/// may name it, and running the 'cuối cùng' block whichever way it ends.
+#[derive(Debug)]
+pub struct ThuStatement {
+    pub body: Block,
+    pub handler: Option<(Option<Ident>, Block)>,
+    pub finally: Option<Block>,
+}
+
+impl ThuStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        if Keyword::accept(parser, Keyword::Thu)?.is_none() {
+            return Ok(None);
+        }
+        let body = Block::accept(parser)?;
+        let handler = match Keyword::accept(parser, Keyword::Bat)? {
+            Some(_) => {
+                let name = Ident::accept(parser)?;
+                parser.skip_ws_if_any(true);
+                Some((name, Block::accept(parser)?))
+            }
+            None => None,
+        };
+        let finally = match Keyword::accept(parser, Keyword::CuoiCung)? {
+            Some(_) => Some(Block::accept(parser)?),
+            None => None,
+        };
+        if handler.is_none() && finally.is_none() {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!(
+                        "'{}' or '{}' after the '{}' block",
+                        Keyword::Bat.as_str(),
+                        Keyword::CuoiCung.as_str(),
+                        Keyword::Thu.as_str()
+                    ),
+                }),
+            });
+        }
+        Ok(Some(ThuStatement {
+            body,
+            handler,
+            finally,
+        }))
+    }
+}
+
+/// Raises a value as an exception, e.g. `ném "không hợp lệ"`.
+#[derive(Debug)]
+pub struct NemStatement {
+    pub kw: Keyword,
+    pub value: Expr,
+}
+
+impl NemStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let Some(kw) = Keyword::accept(parser, Keyword::Nem)? else {
+            return Ok(None);
+        };
+        let Some(value) = Expr::accept(parser)? else {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!("a value to throw after '{}'", kw.as_str()),
+                }),
+            });
+        };
+        Ok(Some(NemStatement { kw, value }))
+    }
+}
+
+/// Runs the first of the 'trường hợp' cases whose pattern matches a value
+/// and whose optional 'nếu' guard holds, e.g.
+///
+/// ```text
+/// so khớp điểm thì
+///     trường hợp (0, 0) thì
+///         in("gốc toạ độ")
+///     trường hợp (x, y) nếu x == y thì
+///         in("trên đường chéo")
+///     trường hợp _ thì
+///         in("nơi khác")
+/// ```
+#[derive(Debug)]
+pub struct SoKhopStatement {
+    pub subject: Expr,
+    pub cases: Vec<Case>,
+}
+
+#[derive(Debug)]
+pub struct Case {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Block,
+}
+
+impl SoKhopStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let Some(kw) = Keyword::accept(parser, Keyword::SoKhop)? else {
+            return Ok(None);
+        };
+        let Some(subject) = Expr::accept(parser)? else {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!("a value to match after '{}'", kw.as_str()),
+                }),
+            });
+        };
+        expect_keyword(parser, Keyword::Thi, "the value to match")?;
+        parser.skip_ws_if_any(true);
+        if !matches!(parser.cur_lexeme.kind, lexeme::Kind::Indent) {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: "an indented block of cases".to_string(),
+                }),
+            });
+        }
+        parser.next_non_ws_lexeme(true); // consume indent
+        let mut cases = Vec::new();
+        while let Some(kw) = Keyword::accept(parser, Keyword::TruongHop)? {
+            let Some(pattern) = Pattern::accept(parser)? else {
+                return Err(Diag {
+                    line: parser.cur_line,
+                    span: parser.cur_span(),
+                    data: DiagData::Err(Error::MiscExpecting {
+                        expected: format!("a pattern after '{}'", kw.as_str()),
+                    }),
+                });
+            };
+            parser.skip_ws_if_any(false);
+            let guard = match Keyword::accept(parser, Keyword::Neu)? {
+                Some(kw) => Some(accept_condition(parser, kw)?),
+                None => {
+                    expect_keyword(parser, Keyword::Thi, "the pattern")?;
+                    None
+                }
+            };
+            let body = Block::accept(parser)?;
+            cases.push(Case {
+                pattern,
+                guard,
+                body,
+            });
+            parser.skip_ws_if_any(true);
+        }
+        if cases.is_empty() || !matches!(parser.cur_lexeme.kind, lexeme::Kind::Dedent) {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: format!("a case starting with '{}'", Keyword::TruongHop.as_str()),
+                }),
+            });
+        }
+        parser.next_non_ws_lexeme(true); // consume dedent
+        Ok(Some(SoKhopStatement { subject, cases }))
+    }
+}
+
+#[derive(Debug)]
+pub struct TraVeStatement {
+    pub kw: Keyword,
+    pub value: Option<Expr>,
+}
+
+impl TraVeStatement {
+    pub fn accept(parser: &mut Parser) -> Result<Option<Self>, Diag> {
+        let span = parser.cur_span();
+        let line = parser.cur_line;
+        let Some(kw) = Keyword::accept(parser, Keyword::TraVe)? else {
+            return Ok(None);
+        };
+        if parser.function_depth == 0 {
+            return Err(Diag {
+                line,
+                span,
+                data: DiagData::Err(Error::OutsideFunction {
+                    keyword: kw.as_str().to_string(),
+                }),
+            });
+        }
+        let value = Expr::accept(parser)?;
+        Ok(Some(TraVeStatement { kw, value }))
+    }
+}
+
+#[derive(Debug)]
+pub enum AssignmentKind {
+    Plain,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl AssignmentKind {
+    pub fn accept(parser: &mut Parser) -> Option<Self> {
+        let kind = match parser.cur_lexeme.kind {
+            lexeme::Kind::Equal => AssignmentKind::Plain,
+            lexeme::Kind::PlusEqual => AssignmentKind::Add,
+            lexeme::Kind::MinusEqual => AssignmentKind::Subtract,
+            lexeme::Kind::AsteriskEqual => AssignmentKind::Multiply,
+            lexeme::Kind::SlashEqual => AssignmentKind::Divide,
+            _ => return None,
+        };
+        parser.next_non_ws_lexeme(false); // consume the operator
+        Some(kind)
+    }
+}
+
+#[derive(Debug)]
+pub enum AssignmentTarget {
+    Name(Ident),
+    /// A field of an instance, e.g. `bản thân.tên`.
+    Member {
+        object: PostfixedExpr,
+        member: Ident,
+    },
+    /// An element of a container, e.g. `a[0]` or `d["khóa"]`.
+    Index {
+        container: PostfixedExpr,
+        index: Expr,
+    },
+}
+
+/// Changes the value of an already declared name or of an element, e.g.
+/// `x = 1`, `x += 1` or `a[0] = 5`.
+#[derive(Debug)]
+pub struct AssignmentStatement {
+    pub lhs: AssignmentTarget,
+    pub kind: AssignmentKind,
+    pub rhs: Expr,
+}
+
+impl AssignmentStatement {
+    /// Accepts the rest of an assignment whose operator has been consumed.
+    pub fn accept(
+        parser: &mut Parser,
+        target: PostfixedExpr,
+        kind: AssignmentKind,
+    ) -> Result<Self, Diag> {
+        let lhs = match target {
+            PostfixedExpr {
+                terminal: TerminalExpr::Literal(Literal::Ident(ident)),
+                postfixes,
+            } if postfixes.is_empty() => AssignmentTarget::Name(ident),
+            PostfixedExpr {
+                terminal,
+                mut postfixes,
+            } if matches!(
+                postfixes.last(),
+                Some((_, Postfix::Subscript(Subscript::Index(_))))
+            ) =>
+            {
+                let Some((_, Postfix::Subscript(Subscript::Index(index)))) = postfixes.pop() else {
+                    unreachable!("the last postfix is an index");
+                };
+                AssignmentTarget::Index {
+                    container: PostfixedExpr {
+                        terminal,
+                        postfixes,
+                    },
+                    index,
+                }
+            }
+            PostfixedExpr {
+                terminal,
+                mut postfixes,
+            } if matches!(postfixes.last(), Some((_, Postfix::Member(_)))) => {
+                let Some((_, Postfix::Member(member))) = postfixes.pop() else {
+                    unreachable!("the last postfix is a member");
+                };
+                AssignmentTarget::Member {
+                    object: PostfixedExpr {
+                        terminal,
+                        postfixes,
+                    },
+                    member,
+                }
+            }
+            _ => {
+                return Err(Diag {
+                    line: parser.cur_line,
+                    span: parser.cur_span(),
+                    data: DiagData::Err(Error::MiscExpecting {
+                        expected:
+                            "a name, an index or a member on the left-hand side of the assignment"
+                                .to_string(),
+                    }),
+                });
+            }
         };
-        Ok(Some(InvocationStatement { callee, tuple }))
+        let Some(rhs) = Expr::accept(parser)? else {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: parser.cur_span(),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: "the right-hand side expression for assignment".to_string(),
+                }),
+            });
+        };
+        Ok(AssignmentStatement { lhs, kind, rhs })
+    }
+}
+
+/// A call made for its effects, e.g. `in(x)` or `f(1)(2)`.
+#[derive(Debug)]
+pub struct InvocationStatement {
+    pub call: PostfixedExpr,
+}
+
+impl InvocationStatement {
+    /// Accepts a statement that began with `call` but is not an assignment.
+    pub fn accept(parser: &mut Parser, call: PostfixedExpr) -> Result<Self, Diag> {
+        if !call.ends_with_call() {
+            return Err(Diag {
+                line: parser.cur_line,
+                span: (parser.cur_pos, 1),
+                data: DiagData::Err(Error::MiscExpecting {
+                    expected: "a tuple of arguments for invocation".to_string(),
+                }),
+            });
+        }
+        Ok(InvocationStatement { call })
     }
 }
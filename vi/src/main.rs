@@ -1,6 +1,6 @@
-use std::{env, fs::File, io::Read, process};
+use std::{env, fs::File, io::Read, process, thread};
 
-use interp::Evaluator;
+use interp::{DivisionByZero, Evaluator, STACK_SIZE};
 
 enum Operation {
     Parse,
@@ -8,6 +8,17 @@ enum Operation {
 }
 
 fn main() {
+    let interpreter = thread::Builder::new()
+        // Deeply recursive programmes need more stack than the main thread has.
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("failed to spawn the interpreter thread");
+    if interpreter.join().is_err() {
+        process::exit(1);
+    }
+}
+
+fn run() {
     let args = env::args().collect::<Vec<String>>();
     if args.len() < 3 {
         eprintln!("Specify an operation and include a source file!");
@@ -22,6 +33,18 @@ fn main() {
         }
     };
     let source_file_name = &args[2];
+    let mut division_by_zero = DivisionByZero::Ieee754;
+    let mut warn_shadowing = false;
+    for option in &args[3..] {
+        match option.as_str() {
+            "--division-by-zero-error" => division_by_zero = DivisionByZero::Error,
+            "--warn-shadowing" => warn_shadowing = true,
+            _ => {
+                eprintln!("Unknown option: {}", option);
+                process::exit(1);
+            }
+        }
+    }
     let Ok(mut source_file) = File::open(source_file_name) else {
         eprintln!("Could not open source file: {}", source_file_name);
         process::exit(1);
@@ -47,6 +70,8 @@ fn main() {
         }
         Operation::Interpret => {
             let mut evaluator = Evaluator::new(&source_code);
+            evaluator.set_division_by_zero(division_by_zero);
+            evaluator.set_warn_shadowing(warn_shadowing);
             let prog = evaluator.parse();
             evaluator.interpret(&prog);
         }